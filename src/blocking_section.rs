@@ -1,9 +1,46 @@
 // Copyright © 2024 Łukasz Kurowski. All rights reserved.
 // SPDX-License-Identifier: MIT
 
+use std::cell::Cell;
+
 extern "C" {
     pub fn caml_enter_blocking_section();
     pub fn caml_leave_blocking_section();
+    pub fn caml_c_thread_register() -> i32;
+}
+
+thread_local! {
+    /// Whether the current thread was created outside of OCaml, `None` until it is registered.
+    static FOREIGN_THREAD: Cell<Option<bool>> = Cell::new(None);
+    /// Whether the current thread holds the OCaml runtime, `None` outside of
+    /// `releasing_runtime` and `acquiring_runtime`.
+    static RUNTIME_HELD: Cell<Option<bool>> = Cell::new(None);
+}
+
+/// Registers the current thread with OCaml unless it is already known to it and returns whether
+/// it is a foreign thread.
+fn register_thread() -> bool {
+    FOREIGN_THREAD.with(|foreign| match foreign.get() {
+        Some(foreign) => foreign,
+        None => {
+            // Zero when the thread is already registered, but also when systhreads is not
+            // initialized. The library depends on async, which links threads.posix and
+            // initializes systhreads at startup, so only the first case happens here.
+            let registered = unsafe { caml_c_thread_register() } != 0;
+            foreign.set(Some(registered));
+            registered
+        }
+    })
+}
+
+fn with_runtime_held<T, F>(held: bool, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let prev = RUNTIME_HELD.with(|cell| cell.replace(Some(held)));
+    let result = f();
+    RUNTIME_HELD.with(|cell| cell.set(prev));
+    result
 }
 
 /// This module provides a way to run a closure in a section with released OCaml runtime.
@@ -32,5 +69,34 @@ pub fn releasing_runtime<T, F>(f: F) -> T
 where
     F: FnOnce() -> T,
 {
-    OCamlBlockingSection::default().perform(f)
+    OCamlBlockingSection::default().perform(|| with_runtime_held(false, f))
+}
+
+/// Run the given closure with the OCaml runtime acquired from a thread that does not hold it,
+/// either a thread inside `releasing_runtime` or a Polars worker thread. Threads unknown to OCaml
+/// are registered with the runtime on first use.
+pub fn acquiring_runtime<T, F>(f: F) -> T
+where
+    F: FnOnce(&ocaml::Runtime) -> T,
+{
+    register_thread();
+    unsafe { caml_leave_blocking_section() };
+    let result = with_runtime_held(true, || f(unsafe { ocaml::Runtime::recover_handle() }));
+    unsafe { caml_enter_blocking_section() };
+    result
+}
+
+/// Run the given closure with the OCaml runtime held, acquiring it only when the current thread
+/// doesn't hold it already. Destructors of OCaml values use it as they run both on OCaml threads,
+/// e.g. from finalizers, and on Polars worker threads.
+pub fn holding_runtime<T, F>(f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let held = RUNTIME_HELD.with(|cell| cell.get());
+    if held.unwrap_or_else(|| !register_thread()) {
+        f()
+    } else {
+        acquiring_runtime(|_| f())
+    }
 }
@@ -1,10 +1,14 @@
 // Copyright © 2023-2024 Łukasz Kurowski. All rights reserved.
 // SPDX-License-Identifier: MIT
 
+use std::iter::FromIterator;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
 use polars::prelude as pl;
 use polars::prelude::IntoLazy;
 
-use crate::blocking_section::releasing_runtime;
+use crate::blocking_section::{acquiring_runtime, releasing_runtime};
 use crate::{deref, polars0::*, types::*};
 
 fn build_exprs(exprs: &Vec<Expr>) -> Vec<pl::Expr> {
@@ -21,12 +25,92 @@ pub fn ml_lazy_frame(df: DataFramePtr) -> LazyFramePtr {
     Pointer::alloc_custom(LazyFrame(deref!(df).clone().lazy()))
 }
 
+/// Source of a lazy frame implemented by an OCaml function, which is given the projected columns
+/// and the row limit and returns the batches of the frame.
+struct OCamlScan {
+    function: OCamlFunction,
+    schema: pl::SchemaRef,
+}
+
+impl OCamlScan {
+    fn batches(&self, args: &pl::AnonymousScanArgs) -> pl::PolarsResult<Vec<pl::DataFrame>> {
+        let with_columns = args.with_columns.as_ref().map(|cols| cols.to_vec());
+        acquiring_runtime(|rt| {
+            let batches = self.function.call2(rt, with_columns, args.n_rows)?;
+            Ok(Vec::<DataFramePtr>::from_value(batches)
+                .iter()
+                .map(|df| df.as_ref().deref().clone())
+                .collect())
+        })
+    }
+}
+
+impl pl::AnonymousScan for OCamlScan {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn scan(&self, args: pl::AnonymousScanArgs) -> pl::PolarsResult<pl::DataFrame> {
+        // the function may or may not honour the projection and the row limit
+        let project = |df: pl::DataFrame| match &args.with_columns {
+            Some(cols) => df.select(cols.iter()),
+            None => Ok(df),
+        };
+        let mut df = project(pl::DataFrame::from(self.schema.as_ref()))?;
+        for batch in self.batches(&args)? {
+            df.vstack_mut(&project(batch)?)?;
+        }
+        Ok(df.head(args.n_rows))
+    }
+
+    fn schema(&self, _infer_schema_length: Option<usize>) -> pl::PolarsResult<pl::SchemaRef> {
+        Ok(self.schema.clone())
+    }
+
+    fn allows_projection_pushdown(&self) -> bool {
+        true
+    }
+
+    fn allows_slice_pushdown(&self) -> bool {
+        true
+    }
+}
+
+#[ocaml::func]
+pub fn ml_lazy_scan(
+    schema: Vec<(String, DataType)>,
+    function: OCamlFunction,
+) -> Result<LazyFramePtr, Error> {
+    let schema = Arc::new(pl::Schema::from_iter(
+        schema
+            .iter()
+            .map(|(name, dtype)| pl::Field::new(name, dtype.into())),
+    ));
+    let scan = OCamlScan {
+        function,
+        schema: schema.clone(),
+    };
+    let args = pl::ScanArgsAnonymous {
+        schema: Some(schema),
+        name: "ocaml_scan",
+        ..Default::default()
+    };
+    let df = pl::LazyFrame::anonymous_scan(Arc::new(scan), args)?;
+    Ok(Pointer::alloc_custom(LazyFrame(df)))
+}
+
 #[ocaml::func]
 pub fn ml_lazy_with_columns(df: LazyFramePtr, cols: Vec<Expr>) -> LazyFramePtr {
     let df = deref!(df).clone().with_columns(build_exprs(&cols));
     Pointer::alloc_custom(LazyFrame(df))
 }
 
+#[ocaml::func]
+pub fn ml_lazy_filter(df: LazyFramePtr, predicate: Expr) -> LazyFramePtr {
+    let df = deref!(df).clone().filter(pl::Expr::from(&predicate));
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
 #[ocaml::func]
 pub fn ml_lazy_groupby_agg(df: LazyFramePtr, groupby: Vec<Expr>, agg: Vec<Expr>) -> LazyFramePtr {
     let df = deref!(df)
@@ -37,10 +121,175 @@ pub fn ml_lazy_groupby_agg(df: LazyFramePtr, groupby: Vec<Expr>, agg: Vec<Expr>)
 }
 
 #[ocaml::func]
-pub fn ml_lazy_sort(df: LazyFramePtr, by_column: &str, opts: SortMultipleOptions) -> LazyFramePtr {
+pub fn ml_lazy_group_by_dynamic(
+    df: LazyFramePtr,
+    index_column: Expr,
+    by: Vec<Expr>,
+    opts: DynamicGroupOptions,
+    agg: Vec<Expr>,
+) -> LazyFramePtr {
     let df = deref!(df)
         .clone()
-        .sort([by_column], pl::SortMultipleOptions::from(&opts));
+        .group_by_dynamic(
+            pl::Expr::from(&index_column),
+            build_exprs(&by),
+            pl::DynamicGroupOptions::from(&opts),
+        )
+        .agg(build_exprs(&agg));
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_group_by_rolling(
+    df: LazyFramePtr,
+    index_column: Expr,
+    by: Vec<Expr>,
+    opts: RollingGroupOptions,
+    agg: Vec<Expr>,
+) -> LazyFramePtr {
+    let df = deref!(df)
+        .clone()
+        .rolling(
+            pl::Expr::from(&index_column),
+            build_exprs(&by),
+            pl::RollingGroupOptions::from(&opts),
+        )
+        .agg(build_exprs(&agg));
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_join(
+    df: LazyFramePtr,
+    other: LazyFramePtr,
+    left_on: Vec<Expr>,
+    right_on: Vec<Expr>,
+    args: JoinArgs,
+) -> LazyFramePtr {
+    let df = deref!(df).clone().join(
+        deref!(other).clone(),
+        build_exprs(&left_on),
+        build_exprs(&right_on),
+        pl::JoinArgs::from(&args),
+    );
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_join_asof(
+    df: LazyFramePtr,
+    other: LazyFramePtr,
+    left_on: Expr,
+    right_on: Expr,
+    opts: AsOfOptions,
+) -> LazyFramePtr {
+    let df = deref!(df).clone().join(
+        deref!(other).clone(),
+        [pl::Expr::from(&left_on)],
+        [pl::Expr::from(&right_on)],
+        pl::JoinArgs::from(&opts),
+    );
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+fn lazy_frames(dfs: &Vec<LazyFramePtr>) -> Vec<pl::LazyFrame> {
+    dfs.iter().map(|df| df.as_ref().deref().clone()).collect()
+}
+
+#[ocaml::func]
+pub fn ml_lazy_concat(dfs: Vec<LazyFramePtr>, args: UnionArgs) -> Result<LazyFramePtr, Error> {
+    let df = pl::concat(lazy_frames(&dfs), pl::UnionArgs::from(&args))?;
+    Ok(Pointer::alloc_custom(LazyFrame(df)))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_concat_horizontal(
+    dfs: Vec<LazyFramePtr>,
+    args: UnionArgs,
+) -> Result<LazyFramePtr, Error> {
+    let df = pl::concat_lf_horizontal(lazy_frames(&dfs), pl::UnionArgs::from(&args))?;
+    Ok(Pointer::alloc_custom(LazyFrame(df)))
+}
+
+/// Polars only supports a single `nulls_last` for all sort keys, so when it differs between
+/// columns it is emulated by sorting on `is_null` of each column before the column itself.
+fn build_sort_exprs(
+    by: &Vec<Expr>,
+    nulls_last: &Vec<bool>,
+    opts: &SortMultipleOptions,
+) -> Result<(Vec<pl::Expr>, pl::SortMultipleOptions), Error> {
+    let mut opts = pl::SortMultipleOptions::from(opts);
+    let by = build_exprs(by);
+    // a single value applies to every sort key
+    let broadcast = |name: &str, v: &Vec<bool>| match v.len() {
+        1 => Ok(vec![v[0]; by.len()]),
+        len if len == by.len() => Ok(v.clone()),
+        len => Err(error_msg(format!(
+            "{} has {} values for {} sort keys",
+            name,
+            len,
+            by.len()
+        ))),
+    };
+    let nulls_last = broadcast("nulls_last", nulls_last)?;
+    let descending = broadcast("descending", &opts.descending)?;
+    if nulls_last.windows(2).all(|w| w[0] == w[1]) {
+        if let Some(nulls_last) = nulls_last.first() {
+            opts.nulls_last = *nulls_last;
+        }
+        opts.descending = descending;
+        return Ok((by, opts));
+    }
+    let mut exprs = Vec::with_capacity(by.len() * 2);
+    opts.descending = Vec::with_capacity(by.len() * 2);
+    for ((expr, nulls_last), descending) in by.into_iter().zip(nulls_last).zip(descending) {
+        exprs.push(expr.clone().is_null());
+        opts.descending.push(!nulls_last);
+        exprs.push(expr);
+        opts.descending.push(descending);
+    }
+    Ok((exprs, opts))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_sort(
+    df: LazyFramePtr,
+    by: Vec<Expr>,
+    nulls_last: Vec<bool>,
+    opts: SortMultipleOptions,
+) -> Result<LazyFramePtr, Error> {
+    let (by, opts) = build_sort_exprs(&by, &nulls_last, &opts)?;
+    let df = deref!(df).clone().sort_by_exprs(by, opts);
+    Ok(Pointer::alloc_custom(LazyFrame(df)))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_top_k(
+    df: LazyFramePtr,
+    k: usize,
+    by: Vec<Expr>,
+    opts: SortMultipleOptions,
+) -> LazyFramePtr {
+    let df = deref!(df).clone().top_k(
+        k as pl::IdxSize,
+        build_exprs(&by),
+        pl::SortMultipleOptions::from(&opts),
+    );
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_bottom_k(
+    df: LazyFramePtr,
+    k: usize,
+    by: Vec<Expr>,
+    opts: SortMultipleOptions,
+) -> LazyFramePtr {
+    let df = deref!(df).clone().bottom_k(
+        k as pl::IdxSize,
+        build_exprs(&by),
+        pl::SortMultipleOptions::from(&opts),
+    );
     Pointer::alloc_custom(LazyFrame(df))
 }
 
@@ -51,8 +300,296 @@ pub fn ml_lazy_select(df: LazyFramePtr, exprs: Vec<Expr>) -> LazyFramePtr {
 }
 
 #[ocaml::func]
-pub fn ml_lazy_collect(df: LazyFramePtr) -> Result<DataFramePtr, Error> {
+pub fn ml_lazy_limit(df: LazyFramePtr, n: usize) -> LazyFramePtr {
+    let df = deref!(df).clone().limit(n as pl::IdxSize);
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_slice(df: LazyFramePtr, offset: isize, length: usize) -> LazyFramePtr {
+    let df = deref!(df)
+        .clone()
+        .slice(offset as i64, length as pl::IdxSize);
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_tail(df: LazyFramePtr, n: usize) -> LazyFramePtr {
+    let df = deref!(df).clone().tail(n as pl::IdxSize);
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_unique(
+    df: LazyFramePtr,
+    subset: Option<Vec<String>>,
+    keep: UniqueKeepStrategy,
+    maintain_order: bool,
+) -> LazyFramePtr {
     let df = deref!(df).clone();
+    let keep = pl::UniqueKeepStrategy::from(&keep);
+    let df = if maintain_order {
+        df.unique_stable(subset, keep)
+    } else {
+        df.unique(subset, keep)
+    };
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_drop_nulls(df: LazyFramePtr, subset: Option<Vec<Expr>>) -> LazyFramePtr {
+    let df = deref!(df)
+        .clone()
+        .drop_nulls(subset.as_ref().map(build_exprs));
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_fill_null(df: LazyFramePtr, value: Expr) -> LazyFramePtr {
+    let df = deref!(df).clone().fill_null(pl::Expr::from(&value));
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_fill_null_with_strategy(
+    df: LazyFramePtr,
+    strategy: FillNullStrategy,
+) -> LazyFramePtr {
+    let strategy = pl::FillNullStrategy::from(&strategy);
+    let df = deref!(df)
+        .clone()
+        .select([pl::col("*").fill_null_with_strategy(strategy)]);
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_rename(df: LazyFramePtr, existing: Vec<String>, new: Vec<String>) -> LazyFramePtr {
+    let df = deref!(df).clone().rename(existing, new);
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_drop(df: LazyFramePtr, columns: Vec<String>) -> LazyFramePtr {
+    let df = deref!(df).clone().drop(columns);
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_reverse(df: LazyFramePtr) -> LazyFramePtr {
+    let df = deref!(df).clone().reverse();
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_shift(df: LazyFramePtr, periods: isize, fill_value: Option<Expr>) -> LazyFramePtr {
+    let df = deref!(df).clone();
+    let periods = pl::lit(periods as i64);
+    let df = match fill_value {
+        Some(fill_value) => df.shift_and_fill(periods, pl::Expr::from(&fill_value)),
+        None => df.shift(periods),
+    };
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_schema(df: LazyFramePtr) -> Result<Vec<(String, DataType)>, Error> {
+    let schema = deref!(df).schema()?;
+    schema
+        .iter()
+        .map(|(name, dtype)| match DataType::try_from(dtype) {
+            Ok(dtype) => Ok((name.to_string(), dtype)),
+            Err(e) => Err(error_msg(format!("column {}: {}", name, e))),
+        })
+        .collect()
+}
+
+#[ocaml::func]
+pub fn ml_lazy_explain(df: LazyFramePtr, optimized: bool) -> Result<String, Error> {
+    Ok(deref!(df).explain(optimized)?)
+}
+
+#[ocaml::func]
+pub fn ml_lazy_to_dot(df: LazyFramePtr, optimized: bool) -> Result<String, Error> {
+    Ok(deref!(df).to_dot(optimized)?)
+}
+
+#[ocaml::func]
+pub fn ml_lazy_with_optimization(
+    df: LazyFramePtr,
+    optimization: Optimization,
+    toggle: bool,
+) -> LazyFramePtr {
+    let df = deref!(df).clone();
+    let df = match optimization {
+        Optimization::PredicatePushdown => df.with_predicate_pushdown(toggle),
+        Optimization::ProjectionPushdown => df.with_projection_pushdown(toggle),
+        Optimization::SlicePushdown => df.with_slice_pushdown(toggle),
+        Optimization::CommSubplanElim => df.with_comm_subplan_elim(toggle),
+        Optimization::CommSubexprElim => df.with_comm_subexpr_elim(toggle),
+        Optimization::TypeCoercion => df.with_type_coercion(toggle),
+        Optimization::SimplifyExpr => df.with_simplify_expr(toggle),
+    };
+    Pointer::alloc_custom(LazyFrame(df))
+}
+
+#[ocaml::func]
+pub fn ml_lazy_collect(df: LazyFramePtr, streaming: bool) -> Result<DataFramePtr, Error> {
+    let df = deref!(df).clone().with_streaming(streaming);
     let df = releasing_runtime(|| df.collect())?;
     Ok(Pointer::alloc_custom(DataFrame(df)))
 }
+
+/// The frames are collected as inputs of a single union so that common subplan elimination
+/// runs over all of them and subplans shared between frames are computed once. Each input
+/// hands its result over from a map node and passes an empty frame on to the union.
+#[ocaml::func]
+pub fn ml_lazy_collect_all(
+    dfs: Vec<LazyFramePtr>,
+    streaming: bool,
+) -> Result<Vec<DataFramePtr>, Error> {
+    if dfs.is_empty() {
+        return Ok(vec![]);
+    }
+    let results: Vec<Arc<OnceLock<pl::DataFrame>>> =
+        dfs.iter().map(|_| Arc::new(OnceLock::new())).collect();
+    // the map nodes must not change the plans of their inputs
+    let opts = pl::AllowedOptimizations {
+        predicate_pushdown: false,
+        projection_pushdown: false,
+        slice_pushdown: false,
+        ..Default::default()
+    };
+    let inputs = lazy_frames(&dfs)
+        .into_iter()
+        .zip(results.iter().cloned())
+        .map(|(df, result)| {
+            let schema = |_: &pl::Schema| -> pl::PolarsResult<pl::SchemaRef> {
+                Ok(Arc::new(pl::Schema::new()))
+            };
+            df.map(
+                move |df| {
+                    let _ = result.set(df);
+                    Ok(pl::DataFrame::empty())
+                },
+                opts,
+                Some(Arc::new(schema)),
+                Some("COLLECT ALL"),
+            )
+        })
+        .collect::<Vec<_>>();
+    let df = pl::concat(inputs, pl::UnionArgs::default())?
+        .with_comm_subplan_elim(true)
+        .with_streaming(streaming);
+    releasing_runtime(|| df.collect())?;
+    results
+        .iter()
+        .map(|result| match result.get() {
+            Some(df) => Ok(Pointer::alloc_custom(DataFrame(df.clone()))),
+            None => Err(Error::Message("collect_all: a frame produced no result")),
+        })
+        .collect()
+}
+
+#[ocaml::func]
+pub fn ml_lazy_profile(df: LazyFramePtr) -> Result<(DataFramePtr, DataFramePtr), Error> {
+    let df = deref!(df).clone();
+    let (df, timings) = releasing_runtime(|| df.profile())?;
+    Ok((
+        Pointer::alloc_custom(DataFrame(df)),
+        Pointer::alloc_custom(DataFrame(timings)),
+    ))
+}
+
+const SINK_STOPPED: &str = "sink stopped";
+
+fn is_sink_stopped(e: &pl::PolarsError) -> bool {
+    match e {
+        pl::PolarsError::ComputeError(msg) => msg.as_ref() == SINK_STOPPED,
+        pl::PolarsError::Context { error, .. } => is_sink_stopped(error),
+        _ => false,
+    }
+}
+
+/// Batches are passed to the OCaml function from the streaming engine as they are produced, which
+/// blocks the producing thread until the function returns. When it returns `Stop` the query is
+/// aborted and remaining batches are dropped.
+#[ocaml::func]
+pub fn ml_lazy_sink_batches(df: LazyFramePtr, function: OCamlFunction) -> Result<(), Error> {
+    let stopped = Arc::new(AtomicBool::new(false));
+    let sink_stopped = stopped.clone();
+    let sink = move |batch: pl::DataFrame| {
+        if sink_stopped.load(Ordering::Acquire) {
+            return Err(pl::PolarsError::ComputeError(SINK_STOPPED.into()));
+        }
+        let control = acquiring_runtime(|rt| {
+            // another worker may have stopped the sink while this one waited for the runtime
+            if sink_stopped.load(Ordering::Acquire) {
+                return Ok(SinkControl::Stop);
+            }
+            let arg: DataFramePtr = Pointer::alloc_custom(DataFrame(batch.clone()));
+            function.call(rt, arg).map(SinkControl::from_value)
+        })?;
+        match control {
+            SinkControl::Continue => Ok(batch.clear()),
+            SinkControl::Stop => {
+                sink_stopped.store(true, Ordering::Release);
+                Err(pl::PolarsError::ComputeError(SINK_STOPPED.into()))
+            }
+        }
+    };
+    let opts = pl::AllowedOptimizations {
+        streaming: true,
+        ..Default::default()
+    };
+    let df = deref!(df)
+        .clone()
+        .map(sink, opts, None, Some("OCAML SINK"))
+        .with_streaming(true);
+    match releasing_runtime(|| df.collect()) {
+        Ok(_) => Ok(()),
+        Err(e) if stopped.load(Ordering::Acquire) && is_sink_stopped(&e) => Ok(()),
+        Err(e) => Err(error_with_desc(e, "sink batches".to_string())),
+    }
+}
+
+#[ocaml::func]
+pub fn ml_lazy_sink_parquet(
+    df: LazyFramePtr,
+    filepath: String,
+    opts: ParquetWriteOptions,
+) -> Result<(), Error> {
+    let df = deref!(df).clone();
+    let opts = pl::ParquetWriteOptions::from(&opts);
+    releasing_runtime(move || {
+        df.sink_parquet(filepath.clone().into(), opts)
+            .map_err(|e| error_with_desc(e, format!("sink parquet {}", filepath)))
+    })
+}
+
+#[ocaml::func]
+pub fn ml_lazy_sink_ipc(
+    df: LazyFramePtr,
+    filepath: String,
+    opts: IpcWriterOptions,
+) -> Result<(), Error> {
+    let df = deref!(df).clone();
+    let opts = pl::IpcWriterOptions::from(&opts);
+    releasing_runtime(move || {
+        df.sink_ipc(filepath.clone().into(), opts)
+            .map_err(|e| error_with_desc(e, format!("sink ipc {}", filepath)))
+    })
+}
+
+#[ocaml::func]
+pub fn ml_lazy_sink_csv(
+    df: LazyFramePtr,
+    filepath: String,
+    opts: CsvWriterOptions,
+) -> Result<(), Error> {
+    let df = deref!(df).clone();
+    let opts = pl::CsvWriterOptions::from(&opts);
+    releasing_runtime(move || {
+        df.sink_csv(filepath.clone().into(), opts)
+            .map_err(|e| error_with_desc(e, format!("sink csv {}", filepath)))
+    })
+}
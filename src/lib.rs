@@ -8,12 +8,15 @@ mod blocking_section;
 mod lazy;
 pub mod polars0;
 pub mod series_bigarray;
+mod sql;
 pub mod types;
 
 use std::iter::FromIterator;
 use std::sync::Arc;
 
-use polars::prelude::{IntoLazy, IntoSeries, PolarsUpsample, SerReader};
+use polars::prelude::{
+    DataFrameJoinOps, IntoLazy, IntoSeries, PolarsUpsample, SerReader, SerWriter,
+};
 
 use crate::blocking_section::releasing_runtime;
 pub use crate::{series_bigarray::*, types::*};
@@ -76,6 +79,145 @@ pub unsafe fn ml_df_read_parquet(
     Ok(Pointer::alloc_custom(df.into()))
 }
 
+#[ocaml::func]
+pub fn ml_df_read_ipc(filepath: String, rechunk: bool) -> Result<DataFramePtr, Error> {
+    let df = releasing_runtime(move || {
+        let f = std::fs::File::open(&filepath)?;
+        pl::IpcReader::new(f)
+            .set_rechunk(rechunk)
+            .finish()
+            .map_err(|e| error_with_desc(e, format!("read ipc {}", filepath)))
+    })?;
+    Ok(Pointer::alloc_custom(df.into()))
+}
+
+/// Percent-encodes the characters Hive escapes in partition paths, which includes the
+/// path separators, so that every `key=value` pair is a single path component.
+fn hive_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\u{00}'..='\u{1F}'
+            | '\u{7F}'
+            | '"'
+            | '#'
+            | '%'
+            | '\''
+            | '*'
+            | '/'
+            | ':'
+            | '='
+            | '?'
+            | '\\'
+            | '['
+            | ']'
+            | '^'
+            | '{' => escaped.push_str(&format!("%{:02X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn hive_partition_dir(name: &str, value: pl::AnyValue) -> Result<String, Error> {
+    let value = match value {
+        pl::AnyValue::Null => "__HIVE_DEFAULT_PARTITION__".to_string(),
+        pl::AnyValue::String(s) => hive_escape(s),
+        value => hive_escape(&value.to_string()),
+    };
+    let name = hive_escape(name);
+    if name.is_empty() || name == ".." {
+        return Err(error_msg(format!("invalid partition {}={}", name, value)));
+    }
+    Ok(format!("{}={}", name, value))
+}
+
+/// Returns the index of the next `part-N` file in `dir`, which is zero unless appending.
+fn next_part_index(dir: &std::path::Path, mode: &PartitionWriteMode) -> Result<usize, Error> {
+    if let PartitionWriteMode::Overwrite = mode {
+        if dir.exists() {
+            std::fs::remove_dir_all(dir)?;
+        }
+    }
+    std::fs::create_dir_all(dir)?;
+    let mut next = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let name = entry?.file_name();
+        let idx = name
+            .to_str()
+            .and_then(|name| name.strip_prefix("part-"))
+            .and_then(|name| name.split('.').next())
+            .and_then(|idx| idx.parse::<usize>().ok());
+        if let Some(idx) = idx {
+            next = next.max(idx + 1);
+        }
+    }
+    Ok(next)
+}
+
+fn write_partition_file(
+    df: &mut pl::DataFrame,
+    filepath: &std::path::Path,
+    format: &PartitionFileFormat,
+) -> Result<(), Error> {
+    let f = std::fs::File::create(filepath)?;
+    match format {
+        PartitionFileFormat::Parquet => pl::ParquetWriter::new(f)
+            .set_parallel(false)
+            .finish(df)
+            .map(|_| ()),
+        PartitionFileFormat::Ipc => pl::IpcWriter::new(f).finish(df),
+    }
+    .map_err(|e| error_with_desc(e, format!("write partition {}", filepath.display())))
+}
+
+/// Write a DataFrame split by key columns into a hive-style directory tree
+/// (`key=value/part-N.ext`) under `root` and return the list of written files.
+/// Overwriting only replaces the partitions present in the DataFrame, other partitions
+/// under `root` are left as they are.
+#[ocaml::func]
+pub fn ml_df_write_partitioned(
+    df: DataFramePtr,
+    root: String,
+    opts: WritePartitioned,
+) -> Result<Vec<String>, Error> {
+    if opts.max_rows_per_file == Some(0) {
+        return Err(Error::Message("max_rows_per_file must be positive"));
+    }
+    let df = deref!(df).clone();
+    releasing_runtime(move || {
+        let extension = match opts.format {
+            PartitionFileFormat::Parquet => "parquet",
+            PartitionFileFormat::Ipc => "ipc",
+        };
+        let mut written = vec![];
+        for partition in df.partition_by_stable(opts.partition_by.clone(), true)? {
+            let mut dir = std::path::PathBuf::from(&root);
+            for name in opts.partition_by.iter() {
+                dir.push(hive_partition_dir(name, partition.column(name)?.get(0)?)?);
+            }
+            let partition = if opts.include_key {
+                partition
+            } else {
+                partition.drop_many(&opts.partition_by)
+            };
+            // an empty partition still needs a non-zero step
+            let rows = opts.max_rows_per_file.unwrap_or(partition.height().max(1));
+            let mut idx = next_part_index(&dir, &opts.mode)?;
+            let mut offset = 0;
+            while offset < partition.height() {
+                let mut chunk = partition.slice(offset as i64, rows);
+                let filepath = dir.join(format!("part-{}.{}", idx, extension));
+                write_partition_file(&mut chunk, &filepath, &opts.format)?;
+                written.push(filepath.to_string_lossy().into_owned());
+                offset += rows;
+                idx += 1;
+            }
+        }
+        Ok(written)
+    })
+}
+
 /// Check if all values in DataFrames are equal where `None == None` evaluates to true.
 #[ocaml::func]
 pub fn ml_df_equal(df: DataFramePtr, other: DataFramePtr) -> bool {
@@ -221,6 +363,64 @@ pub fn ml_df_sort_in_place(
     Ok(())
 }
 
+#[ocaml::func]
+pub fn ml_df_join(
+    df: DataFramePtr,
+    other: DataFramePtr,
+    left_on: Vec<String>,
+    right_on: Vec<String>,
+    args: JoinArgs,
+) -> Result<DataFramePtr, Error> {
+    let df = deref!(df).clone();
+    let other = deref!(other).clone();
+    let args = pl::JoinArgs::from(&args);
+    let df = releasing_runtime(move || df.join(&other, left_on, right_on, args))?;
+    Ok(Pointer::alloc_custom(df.into()))
+}
+
+#[ocaml::func]
+pub fn ml_df_join_asof(
+    df: DataFramePtr,
+    other: DataFramePtr,
+    left_on: String,
+    right_on: String,
+    opts: AsOfOptions,
+) -> Result<DataFramePtr, Error> {
+    let df = deref!(df).clone().lazy();
+    let other = deref!(other).clone().lazy();
+    let args = pl::JoinArgs::from(&opts);
+    let df = releasing_runtime(move || {
+        df.join(other, [pl::col(&left_on)], [pl::col(&right_on)], args)
+            .collect()
+    })?;
+    Ok(Pointer::alloc_custom(df.into()))
+}
+
+#[ocaml::func]
+pub fn ml_df_concat(dfs: Vec<DataFramePtr>, args: UnionArgs) -> Result<DataFramePtr, Error> {
+    let dfs: Vec<_> = dfs
+        .iter()
+        .map(|df| df.as_ref().deref().clone().lazy())
+        .collect();
+    let args = pl::UnionArgs::from(&args);
+    let df = releasing_runtime(move || pl::concat(dfs, args)?.collect())?;
+    Ok(Pointer::alloc_custom(df.into()))
+}
+
+#[ocaml::func]
+pub fn ml_df_concat_horizontal(dfs: Vec<DataFramePtr>) -> Result<DataFramePtr, Error> {
+    let dfs: Vec<_> = dfs.iter().map(|df| df.as_ref().deref().clone()).collect();
+    let df = releasing_runtime(move || polars::functions::concat_df_horizontal(&dfs))?;
+    Ok(Pointer::alloc_custom(df.into()))
+}
+
+#[ocaml::func]
+pub fn ml_df_hstack(df: DataFramePtr, columns: Vec<SeriesPtr>) -> Result<DataFramePtr, Error> {
+    let columns: Vec<_> = columns.iter().map(|s| s.as_ref().deref().clone()).collect();
+    let df = deref!(df).hstack(&columns)?;
+    Ok(Pointer::alloc_custom(df.into()))
+}
+
 #[ocaml::func]
 pub fn ml_df_upsample(
     df: DataFramePtr,
@@ -315,9 +515,16 @@ pub fn ml_series_multiply(series: SeriesPtr, right: AnyValue) -> Result<SeriesPt
         AnyValue::Float32(v) => Ok(series * v),
         AnyValue::Float64(v) => Ok(series * v),
         AnyValue::Datetime(v, _) => Ok(series * v),
+        AnyValue::UInt32(v) => Ok(series * v),
         AnyValue::Boolean(_) => Err(Error::Message(
             "the trait `NumCast` is not implemented for `bool`",
         )),
+        AnyValue::String(_) => Err(Error::Message(
+            "the trait `NumCast` is not implemented for `String`",
+        )),
+        AnyValue::Date(_) => Err(Error::Message("cannot multiply a series by a date")),
+        AnyValue::Time(_) => Err(Error::Message("cannot multiply a series by a time")),
+        AnyValue::Duration(_, _) => Err(Error::Message("cannot multiply a series by a duration")),
     }?;
     Ok(Pointer::alloc_custom(series.into()))
 }
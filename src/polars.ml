@@ -34,11 +34,24 @@ module Dtype = struct
     | Float64
     | Boolean
     | Datetime of time_unit
+    | UInt32
+    | String
+    | Date
+    | Time
+    | Duration of time_unit
+    | Int8
+    | Int16
+    | Int32
+    | UInt8
+    | UInt16
+    | UInt64
+    | List of t
+    | Struct of (string * t) array
   [@@deriving compare, equal, sexp]
 end
 
 module Series = struct
-  type t
+  type t = series
 
   external create : string -> Any_bigarray.t -> copy:bool -> t = "ml_series_create"
 
@@ -107,6 +120,68 @@ let sort_in_place ?descending ?maintain_order df ~col =
   sort_cols_in_place df ~cols:[| col |] ?descending ?maintain_order
 ;;
 
+(* joins *)
+external join
+  :  t
+  -> t
+  -> string array
+  -> string array
+  -> join_args
+  -> t
+  = "ml_df_join"
+
+let join
+      ?(how = Inner)
+      ?(validation = ManyToMany)
+      ?suffix
+      ?(join_nulls = false)
+      t
+      other
+      ~left_on
+      ~right_on
+  =
+  join t other left_on right_on { how; validation; suffix; join_nulls }
+;;
+
+external join_asof
+  :  t
+  -> t
+  -> string
+  -> string
+  -> as_of_options
+  -> t
+  = "ml_df_join_asof"
+
+let join_asof
+      ?(strategy = (Backward : asof_strategy))
+      ?tolerance
+      ?left_by
+      ?right_by
+      ?suffix
+      t
+      other
+      ~left_on
+      ~right_on
+  =
+  join_asof t other left_on right_on { strategy; tolerance; left_by; right_by; suffix }
+;;
+
+(* concatenation *)
+external concat : t array -> union_args -> t = "ml_df_concat"
+
+let concat
+      ?(parallel = true)
+      ?(rechunk = true)
+      ?(to_supertypes = false)
+      ?(diagonal = false)
+      ts
+  =
+  concat ts { parallel; rechunk; to_supertypes; diagonal }
+;;
+
+external concat_horizontal : t array -> t = "ml_df_concat_horizontal"
+external hstack : t -> Series.t array -> t = "ml_df_hstack"
+
 module Duration = struct
   type t = duration [@@deriving sexp]
 
@@ -220,10 +295,33 @@ external read_parquet
   -> t
   = "ml_df_read_parquet"
 
+external read_ipc : string -> rechunk:bool -> t = "ml_df_read_ipc"
+
+let read_ipc ?(rechunk = false) path = read_ipc ~rechunk path
+
 external write_parquet : t -> string -> int = "ml_df_write_parquet"
 
 let write_parquet' t f = In_thread.run (fun () -> write_parquet t f)
 
+external write_partitioned
+  :  t
+  -> string
+  -> write_partitioned
+  -> string array
+  = "ml_df_write_partitioned"
+
+let write_partitioned
+      ?(format = Parquet)
+      ?max_rows_per_file
+      ?(mode = Overwrite)
+      ?(include_key = false)
+      t
+      ~partition_by
+      path
+  =
+  write_partitioned t path { partition_by; format; max_rows_per_file; mode; include_key }
+;;
+
 let read_parquet ?(rechunk = false) ?(parallel = false) path =
   read_parquet ~rechunk ~parallel path
 ;;
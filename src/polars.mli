@@ -27,6 +27,19 @@ module Dtype : sig
     | Float64
     | Boolean
     | Datetime of time_unit
+    | UInt32
+    | String
+    | Date
+    | Time
+    | Duration of time_unit
+    | Int8
+    | Int16
+    | Int32
+    | UInt8
+    | UInt16
+    | UInt64
+    | List of t
+    | Struct of (string * t) array
   [@@deriving compare, equal, sexp]
 end
 
@@ -39,7 +52,7 @@ module Schema : sig
 end
 
 module Series : sig
-  type t [@@deriving sexp_of]
+  type t = series [@@deriving sexp_of]
 
   (** [create ~copy name data] creates a series from a name and bigarray. If [copy] is
       [false], the bigarray should be kept alive for the lifetime of the series. *)
@@ -150,6 +163,55 @@ val sort_cols_in_place
     in place. *)
 val sort_in_place : ?descending:bool -> ?maintain_order:bool -> t -> col:string -> unit
 
+(** [join ?how ?validation ?suffix ?join_nulls t other ~left_on ~right_on] joins [t] with
+    [other] on columns [left_on] and [right_on]. Clashing column names from [other] get
+    the [suffix] appended, [validation] checks the uniqueness of join keys. *)
+val join
+  :  ?how:join_type
+  -> ?validation:join_validation
+  -> ?suffix:string
+  -> ?join_nulls:bool
+  -> t
+  -> t
+  -> left_on:string array
+  -> right_on:string array
+  -> t
+
+(** [join_asof ?strategy ?tolerance ?left_by ?right_by ?suffix t other ~left_on
+    ~right_on] joins every row of [t] with the nearest row of [other] by the [left_on]
+    and [right_on] keys, matching exactly on [left_by] and [right_by] columns. Both keys
+    must be sorted; set the flag with [Series.set_sorted_flag] if the data is already
+    sorted. A [tolerance] requires temporal keys, [Slots] count nanoseconds. *)
+val join_asof
+  :  ?strategy:asof_strategy
+  -> ?tolerance:duration
+  -> ?left_by:string array
+  -> ?right_by:string array
+  -> ?suffix:string
+  -> t
+  -> t
+  -> left_on:string
+  -> right_on:string
+  -> t
+
+(** [concat ?parallel ?rechunk ?to_supertypes ?diagonal ts] stacks the dataframes [ts]
+    vertically. With [to_supertypes] columns are cast to a common type, with [diagonal]
+    missing columns are filled with nulls instead of raising. *)
+val concat
+  :  ?parallel:bool
+  -> ?rechunk:bool
+  -> ?to_supertypes:bool
+  -> ?diagonal:bool
+  -> t array
+  -> t
+
+(** [concat_horizontal ts] joins the columns of the dataframes [ts] side by side, shorter
+    frames are padded with nulls. *)
+val concat_horizontal : t array -> t
+
+(** [hstack t columns] returns [t] with the series [columns] appended. *)
+val hstack : t -> Series.t array -> t
+
 module Duration : sig
   type t = duration [@@deriving sexp]
 
@@ -237,6 +299,22 @@ val write_parquet : t -> string -> int
     at [file_path]. *)
 val write_parquet' : t -> string -> int Deferred.t
 
+(** [write_partitioned ?format ?max_rows_per_file ?mode ?include_key t ~partition_by
+    root] writes the dataframe [t] split by [partition_by] columns into a hive-style
+    directory tree ([key=value/part-N.parquet]) under [root] and returns the list of
+    written files. With [mode = Overwrite] the directories of the written partitions are
+    removed first, partitions absent from [t] are kept. With [Append] new files are
+    numbered after the existing ones. Raises if [max_rows_per_file] is not positive. *)
+val write_partitioned
+  :  ?format:partition_file_format
+  -> ?max_rows_per_file:int
+  -> ?mode:partition_write_mode
+  -> ?include_key:bool
+  -> t
+  -> partition_by:string array
+  -> string
+  -> string array
+
 (** [read_parquet ?rechunk ?parallel file_path] reads a Parquet file at [file_path] into a
     dataframe. *)
 val read_parquet : ?rechunk:bool -> ?parallel:bool -> string -> t
@@ -245,6 +323,10 @@ val read_parquet : ?rechunk:bool -> ?parallel:bool -> string -> t
     [file_path] into a dataframe. *)
 val read_parquet' : ?rechunk:bool -> ?parallel:bool -> string -> t Deferred.t
 
+(** [read_ipc ?rechunk file_path] reads an Arrow IPC file at [file_path] into a
+    dataframe. *)
+val read_ipc : ?rechunk:bool -> string -> t
+
 (** [filter_col_by_name t ~col cmp value] filters the dataframe [t] by applying the
     comparison [cmp] to the column [col] and [value]. *)
 val filter_col_by_name : t -> col:string -> comparison -> any_value -> t
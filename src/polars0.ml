@@ -4,3 +4,8 @@
 type t
 type series
 type lazy_frame
+type sql_context
+type sql_expr
+
+(* OCaml closures passed to Rust, defined here as types.ml is generated from types.rs *)
+type series_udf = series -> series
@@ -1,14 +1,19 @@
 // Copyright © 2023-2024 Łukasz Kurowski. All rights reserved.
 // SPDX-License-Identifier: MIT
 
+use std::sync::Arc;
 pub use std::{
     convert::TryFrom,
     fmt,
     ops::{Deref, DerefMut},
 };
 
+use crate::blocking_section::{acquiring_runtime, holding_runtime};
+
 pub mod pl {
+    pub use polars::sql::{sql_expr, SQLContext};
     pub use polars::{lazy::dsl::WindowMapping, prelude::*, series::IsSorted};
+    pub use polars_plan::dsl::function_expr::trigonometry::TrigonometricFunction;
     pub use polars_plan::dsl::{max_horizontal, min_horizontal, sum_horizontal};
     pub use polars_plan::prelude::{ApplyOptions, FunctionOptions, WindowType};
 }
@@ -62,6 +67,8 @@ macro_rules! make_custom {
 make_custom!(Series, pl::Series, SeriesPtr);
 make_custom!(DataFrame, pl::DataFrame, DataFramePtr);
 make_custom!(LazyFrame, pl::LazyFrame, LazyFramePtr);
+make_custom!(SQLContext, pl::SQLContext, SQLContextPtr);
+make_custom!(SQLExpr, pl::Expr, SQLExprPtr);
 
 #[derive(Debug)]
 pub struct ErrorWithDesc {
@@ -98,3 +105,94 @@ impl std::fmt::Display for ErrorMessage {
 pub fn error_msg<S: ToString>(s: S) -> Error {
     Error::Error(Box::new(ErrorMessage(s.to_string())))
 }
+
+/// Rooted OCaml value, the root is released with the OCaml runtime held on whichever thread
+/// drops the last reference.
+struct OCamlRoot(std::mem::ManuallyDrop<ocaml::Value>);
+
+impl Drop for OCamlRoot {
+    fn drop(&mut self) {
+        let value = unsafe { std::mem::ManuallyDrop::take(&mut self.0) };
+        holding_runtime(move || drop(value));
+    }
+}
+
+/// OCaml closure kept alive by a root so that it outlives the call that passed it to Rust. It is
+/// called from Polars worker threads, which acquire the OCaml runtime for the duration of the call.
+#[derive(Clone)]
+pub struct OCamlFunction(Arc<OCamlRoot>);
+
+unsafe impl Send for OCamlFunction {}
+unsafe impl Sync for OCamlFunction {}
+
+fn ocaml_exception(e: ocaml::Error) -> pl::PolarsError {
+    pl::PolarsError::ComputeError(format!("OCaml function raised: {:?}", e).into())
+}
+
+impl OCamlFunction {
+    fn raw(&self) -> ocaml::Raw {
+        self.0 .0.raw()
+    }
+
+    pub fn call<A: ToValue>(&self, rt: &ocaml::Runtime, arg: A) -> pl::PolarsResult<ocaml::Value> {
+        unsafe { self.0 .0.call(rt, arg) }.map_err(ocaml_exception)
+    }
+
+    pub fn call2<A: ToValue, B: ToValue>(
+        &self,
+        rt: &ocaml::Runtime,
+        arg1: A,
+        arg2: B,
+    ) -> pl::PolarsResult<ocaml::Value> {
+        unsafe { self.0 .0.call2(rt, arg1, arg2) }.map_err(ocaml_exception)
+    }
+}
+
+unsafe impl FromValue for OCamlFunction {
+    fn from_value(value: ocaml::Value) -> Self {
+        let root = unsafe { ocaml::Value::new(value.raw()) };
+        OCamlFunction(Arc::new(OCamlRoot(std::mem::ManuallyDrop::new(root))))
+    }
+}
+
+/// OCaml closure `Series.t -> Series.t` applied to batches of an expression.
+#[derive(Clone)]
+pub struct SeriesUdf(OCamlFunction);
+
+impl SeriesUdf {
+    pub(crate) fn call(&self, series: pl::Series) -> pl::PolarsResult<pl::Series> {
+        acquiring_runtime(|rt| {
+            let arg: SeriesPtr = Pointer::alloc_custom(Series(series));
+            let result = self.0.call(rt, arg)?;
+            Ok(SeriesPtr::from_value(result).as_ref().0.clone())
+        })
+    }
+}
+
+unsafe impl FromValue for SeriesUdf {
+    fn from_value(value: ocaml::Value) -> Self {
+        SeriesUdf(OCamlFunction::from_value(value))
+    }
+}
+
+// the closure is an OCaml value already, so it is passed back as is
+impl ocamlrep::ToOcamlRep for SeriesUdf {
+    fn to_ocamlrep<'a, A: ocamlrep::Allocator>(&'a self, _alloc: &'a A) -> ocamlrep::Value<'a> {
+        unsafe { ocamlrep::Value::from_bits(self.0.raw().0 as usize) }
+    }
+}
+
+/// SQL expression parsed by `ml_sql_expr` when the expression is built.
+pub struct ParsedSqlExpr(pub SQLExprPtr);
+
+unsafe impl FromValue for ParsedSqlExpr {
+    fn from_value(value: ocaml::Value) -> Self {
+        ParsedSqlExpr(SQLExprPtr::from_value(value))
+    }
+}
+
+impl ocamlrep::ToOcamlRep for ParsedSqlExpr {
+    fn to_ocamlrep<'a, A: ocamlrep::Allocator>(&'a self, _alloc: &'a A) -> ocamlrep::Value<'a> {
+        unsafe { ocamlrep::Value::from_bits(self.0 .0.raw().0 as usize) }
+    }
+}
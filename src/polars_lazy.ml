@@ -60,10 +60,47 @@ module Builder = struct
   let fill_null ~with_ expr = map ~cast_to_supertypes:true [| expr; with_ |] FillNull
   let forward_fill ?limit expr = ForwardFill (expr, limit)
 
+  (* user-defined functions, [f] must return a series of [output_type] *)
+  let map_batches ~output_type f expr =
+    MapBatches { input = expr; function_ = f; output_type }
+  ;;
+
   (* rounding *)
   let floor expr = map [| expr |] Floor
   let ceil expr = map [| expr |] Ceil
 
+  (* math *)
+  let pow exponent expr = map [| expr; exponent |] (Pow Generic)
+  let sqrt expr = map [| expr |] (Pow Sqrt)
+  let cbrt expr = map [| expr |] (Pow Cbrt)
+  let sign expr = map [| expr |] Sign
+
+  (* bounds values to [min] and [max], either bound may be omitted *)
+  let clip ?min ?max expr =
+    let has_min = Option.is_some min
+    and has_max = Option.is_some max in
+    map (Array.filter_opt [| Some expr; min; max |]) (Clip { has_min; has_max })
+  ;;
+
+  (* trigonometry *)
+  let trigonometry function_ expr = map [| expr |] (Trigonometry function_)
+  let cos = trigonometry Cos
+  let cot = trigonometry Cot
+  let sin = trigonometry Sin
+  let tan = trigonometry Tan
+  let arccos = trigonometry ArcCos
+  let arcsin = trigonometry ArcSin
+  let arctan = trigonometry ArcTan
+  let cosh = trigonometry Cosh
+  let sinh = trigonometry Sinh
+  let tanh = trigonometry Tanh
+  let arccosh = trigonometry ArcCosh
+  let arcsinh = trigonometry ArcSinh
+  let arctanh = trigonometry ArcTanh
+  let degrees = trigonometry Degrees
+  let radians = trigonometry Radians
+  let atan2 y x = map [| y; x |] Atan2
+
   (* literal *)
   let null = Literal Null
   let lit lit = Literal lit
@@ -82,6 +119,12 @@ module Builder = struct
   let max expr = Agg (Max { input = expr; propagate_nans = false })
   let min expr = Agg (Min { input = expr; propagate_nans = false })
   let sum expr = Agg (Sum expr)
+  let implode expr = Agg (Implode expr)
+
+  let quantile ?(interpol = (Nearest : quantile_interpol_options)) quantile expr =
+    Agg (Quantile { expr; quantile; interpol })
+  ;;
+
   let select exprs = Columns exprs
 
   let sort_by ?(ascending = false) expr column_name =
@@ -97,6 +140,12 @@ module Builder = struct
 
   let collect expr = expr
 
+  (* sql *)
+  external parse_sql_expr : string -> sql_expr = "ml_sql_expr"
+
+  (* parses a SQL expression such as ["price * qty AS notional"], raises if invalid *)
+  let sql_expr sql = Sql (parse_sql_expr sql)
+
   (* horizontal *)
   let horizontal op input = Horizontal { input; op }
   let min_horizontal = horizontal Min
@@ -132,7 +181,11 @@ module Builder = struct
   let rolling_mean = rolling Mean
   let rolling_sum = rolling Sum
   let rolling_median = rolling Median
-  let rolling_quantile q = rolling (Quantile q)
+
+  let rolling_quantile ?(interpol = (Nearest : quantile_interpol_options)) q =
+    rolling (Quantile (q, interpol))
+  ;;
+
   let rolling_var = rolling Var
   let rolling_std = rolling Std
 
@@ -142,6 +195,184 @@ module Builder = struct
   let cumprod ?(reverse = false) expr = apply expr (CumProd { reverse })
   let cummin ?(reverse = false) expr = apply expr (CumMin { reverse })
   let cummax ?(reverse = false) expr = apply expr (CumMax { reverse })
+
+  (* strings, patterns are regular expressions unless [literal] is set *)
+  module Str = struct
+    let fn inputs function_ = map inputs (StringExpr function_)
+    let str s = lit (String s)
+    let str_opt = Option.value_map ~default:null ~f:str
+
+    let contains ?(literal = false) ?(strict = true) pattern expr =
+      fn [| expr; str pattern |] (Contains { literal; strict })
+    ;;
+
+    let count_matches ?(literal = false) pattern expr =
+      fn [| expr; str pattern |] (CountMatches literal)
+    ;;
+
+    let starts_with prefix expr = fn [| expr; str prefix |] StartsWith
+    let ends_with suffix expr = fn [| expr; str suffix |] EndsWith
+
+    let extract ?(group_index = 1) pattern expr =
+      fn [| expr; str pattern |] (Extract group_index)
+    ;;
+
+    let extract_all pattern expr = fn [| expr; str pattern |] ExtractAll
+
+    let replace ?(literal = false) ?(n = 1) ~pattern ~with_ expr =
+      fn [| expr; str pattern; str with_ |] (Replace { n; literal })
+    ;;
+
+    let replace_all ?literal ~pattern ~with_ expr =
+      replace ?literal ~n:(-1) ~pattern ~with_ expr
+    ;;
+
+    let split ?(inclusive = false) by expr = fn [| expr; str by |] (Split inclusive)
+
+    let slice ?length ~offset expr =
+      let length = Option.value_map length ~default:null ~f:int64 in
+      fn [| expr; int64 offset; length |] Slice
+    ;;
+
+    let to_lowercase expr = fn [| expr |] Lowercase
+    let to_uppercase expr = fn [| expr |] Uppercase
+
+    (* without [chars] whitespace is stripped *)
+    let strip_chars ?chars expr = fn [| expr; str_opt chars |] StripChars
+    let strip_chars_start ?chars expr = fn [| expr; str_opt chars |] StripCharsStart
+    let strip_chars_end ?chars expr = fn [| expr; str_opt chars |] StripCharsEnd
+    let strip_prefix prefix expr = fn [| expr; str prefix |] StripPrefix
+    let strip_suffix suffix expr = fn [| expr; str suffix |] StripSuffix
+    let len_bytes expr = fn [| expr |] LenBytes
+    let len_chars expr = fn [| expr |] LenChars
+    let zfill length expr = fn [| expr; int64 length |] ZFill
+
+    let pad_start ?(fill_char = ' ') length expr =
+      fn [| expr |] (PadStart { length; fill_char = Char.to_int fill_char })
+    ;;
+
+    let pad_end ?(fill_char = ' ') length expr =
+      fn [| expr |] (PadEnd { length; fill_char = Char.to_int fill_char })
+    ;;
+
+    let strptime ?format ?(strict = true) ?(exact = true) ?(cache = true) dtype expr =
+      let options = { format; strict; exact; cache } in
+      fn [| expr; str "raise" |] (Strptime (dtype, options))
+    ;;
+
+    let to_date ?format = strptime ?format (Date : data_type)
+    let to_time ?format = strptime ?format (Time : data_type)
+
+    let to_datetime ?format ?(time_unit = Microseconds) =
+      strptime ?format (Datetime time_unit : data_type)
+    ;;
+  end
+
+  (* temporal, durations are either nanoseconds or strings like ["1d12h"] *)
+  module Dt = struct
+    let fn input function_ = TemporalExpr { input; function_ }
+
+    let duration = function
+      | Slots ns -> lit (String (Int.to_string ns ^ "ns"))
+      | Duration duration -> lit (String duration)
+    ;;
+
+    (* Polars returns these as Int8 to Int32, which [Any_value] cannot represent *)
+    let part expr function_ = fn expr function_ |> cast (Int64 : data_type)
+    let year expr = part expr Year
+    let month expr = part expr Month
+    let day expr = part expr Day
+    let weekday expr = part expr WeekDay
+    let ordinal_day expr = part expr OrdinalDay
+    let hour expr = part expr Hour
+    let minute expr = part expr Minute
+    let second expr = part expr Second
+    let millisecond expr = part expr Millisecond
+    let microsecond expr = part expr Microsecond
+    let nanosecond expr = part expr Nanosecond
+    let strftime format expr = fn expr (ToString format)
+    let cast_time_unit time_unit expr = fn expr (CastTimeUnit time_unit)
+    let with_time_unit time_unit expr = fn expr (WithTimeUnit time_unit)
+    let timestamp ?(time_unit = Microseconds) expr = fn expr (TimeStamp time_unit)
+
+    (* seconds since the unix epoch *)
+    let epoch expr = floor_divide (timestamp ~time_unit:Milliseconds expr) (int64 1000)
+
+    let truncate ?(offset = Slots 0) every expr =
+      fn expr (Truncate { every = duration every; offset })
+    ;;
+
+    let round ?(offset = Slots 0) every expr =
+      fn expr (Round { every = duration every; offset })
+    ;;
+
+    let offset_by by expr = map [| expr; duration by |] DateOffset
+    let month_start expr = fn expr MonthStart
+    let month_end expr = fn expr MonthEnd
+  end
+
+  (* named [Lst] to not shadow [List] when [Polars_lazy] is opened *)
+  module Lst = struct
+    let fn input function_ = ListExpr { input; function_ }
+
+    (* refers to the list element in [eval] *)
+    let element = col ""
+    let len expr = fn expr Length
+
+    let get ?(null_on_oob = false) idx expr =
+      fn expr (Get { index = int64 idx; null_on_oob })
+    ;;
+
+    (* [idx] evaluates to a list of indices per row *)
+    let gather ?(null_on_oob = false) idx expr =
+      fn expr (Gather { index = idx; null_on_oob })
+    ;;
+
+    let slice ?length ~offset expr =
+      let length = Option.value_map length ~default:null ~f:int64 in
+      fn expr (Slice { offset = int64 offset; length })
+    ;;
+
+    let head n expr = slice ~offset:0 ~length:n expr
+    let tail n expr = slice ~offset:(Int.neg n) ~length:n expr
+    let sum expr = fn expr Sum
+    let mean expr = fn expr Mean
+    let min expr = fn expr Min
+    let max expr = fn expr Max
+    let contains item expr = fn expr (Contains item)
+    let unique ?(maintain_order = false) expr = fn expr (Unique maintain_order)
+
+    let sort ?(descending = false) ?(nulls_last = false) expr =
+      fn expr (Sort { descending; nulls_last })
+    ;;
+
+    let join ?(ignore_nulls = true) separator expr =
+      fn expr (Join { separator = lit (String separator); ignore_nulls })
+    ;;
+
+    let eval ?(parallel = false) f expr = ListEval { input = expr; expr = f; parallel }
+    let to_struct ~upper_bound expr = ListToStruct { input = expr; upper_bound }
+  end
+
+  (* ranges *)
+  let date_range ?(closed_window = (Both : closed_window)) ~interval start end_ =
+    DateRange { start; end_; interval; closed_window }
+  ;;
+
+  let datetime_range
+        ?(closed_window = (Both : closed_window))
+        ?time_unit
+        ?time_zone
+        ~interval
+        start
+        end_
+    =
+    DatetimeRange { start; end_; interval; closed_window; time_unit; time_zone }
+  ;;
+
+  let time_range ?(closed_window = (Both : closed_window)) ~interval start end_ =
+    TimeRange { start; end_; interval; closed_window }
+  ;;
 end
 
 include Builder
@@ -149,35 +380,413 @@ include Builder
 type t
 
 external create : Polars.t -> t = "ml_lazy_frame"
+
+(* [f columns n_rows] returns the batches of the frame, [columns] is the projection and
+   [n_rows] the row limit pushed down from the query, both can be ignored *)
+external scan
+  :  Schema.t
+  -> (string array option -> int option -> Polars.t array)
+  -> t
+  = "ml_lazy_scan"
+
+let scan ~schema f = scan schema f
+
 external with_columns : t -> expr array -> t = "ml_lazy_with_columns"
 
 let with_columns col t = with_columns t col
 let with_column col = with_columns [| col |]
 
+external filter : t -> expr -> t = "ml_lazy_filter"
+
+let filter predicate t = filter t predicate
+
 external groupby_agg : t -> expr array -> expr array -> t = "ml_lazy_groupby_agg"
 
 let groupby_agg groupby agg t = groupby_agg t groupby agg
 
-external sort : t -> string -> sort_multiple_options -> t = "ml_lazy_sort"
+external group_by_dynamic
+  :  t
+  -> expr
+  -> expr array
+  -> dynamic_group_options
+  -> expr array
+  -> t
+  = "ml_lazy_group_by_dynamic"
+
+let group_by_dynamic
+      ?period
+      ?(offset = Duration "0ns")
+      ?(label = (Left : label))
+      ?(include_boundaries = false)
+      ?(closed_window = (Left : closed_window))
+      ?(start_by = (WindowBound : start_by))
+      ?(check_sorted = true)
+      ?(by = [||])
+      ~every
+      index_column
+      agg
+      t
+  =
+  let period = Option.value period ~default:every in
+  let opts =
+    { every
+    ; period
+    ; offset
+    ; label
+    ; include_boundaries
+    ; closed_window
+    ; start_by
+    ; check_sorted
+    }
+  in
+  group_by_dynamic t index_column by opts agg
+;;
+
+external group_by_rolling
+  :  t
+  -> expr
+  -> expr array
+  -> rolling_group_options
+  -> expr array
+  -> t
+  = "ml_lazy_group_by_rolling"
+
+let group_by_rolling
+      ?offset
+      ?(closed_window = (Right : closed_window))
+      ?(check_sorted = true)
+      ?(by = [||])
+      ~period
+      index_column
+      agg
+      t
+  =
+  (* windows default to [(t - period, t]] *)
+  let offset =
+    match offset, period with
+    | Some offset, _ -> offset
+    | None, Slots slots -> Slots (Int.neg slots)
+    | None, Duration duration -> Duration ("-" ^ duration)
+  in
+  let opts = { period; offset; closed_window; check_sorted } in
+  group_by_rolling t index_column by opts agg
+;;
+
+external join
+  :  t
+  -> t
+  -> expr array
+  -> expr array
+  -> join_args
+  -> t
+  = "ml_lazy_join"
+
+let join
+      ?(how = Inner)
+      ?(validation = ManyToMany)
+      ?suffix
+      ?(join_nulls = false)
+      ~left_on
+      ~right_on
+      other
+      t
+  =
+  join t other left_on right_on { how; validation; suffix; join_nulls }
+;;
+
+let cross_join ?suffix other t =
+  join ~how:Cross ?suffix ~left_on:[||] ~right_on:[||] other t
+;;
+
+external join_asof
+  :  t
+  -> t
+  -> expr
+  -> expr
+  -> as_of_options
+  -> t
+  = "ml_lazy_join_asof"
+
+let join_asof
+      ?(strategy = (Backward : asof_strategy))
+      ?tolerance
+      ?left_by
+      ?right_by
+      ?suffix
+      ~left_on
+      ~right_on
+      other
+      t
+  =
+  join_asof t other left_on right_on { strategy; tolerance; left_by; right_by; suffix }
+;;
+
+external concat : t array -> union_args -> t = "ml_lazy_concat"
+
+let concat
+      ?(parallel = true)
+      ?(rechunk = true)
+      ?(to_supertypes = false)
+      ?(diagonal = false)
+      ts
+  =
+  concat ts { parallel; rechunk; to_supertypes; diagonal }
+;;
+
+external concat_horizontal : t array -> union_args -> t = "ml_lazy_concat_horizontal"
+
+let concat_horizontal ?(parallel = true) ts =
+  concat_horizontal
+    ts
+    { parallel; rechunk = false; to_supertypes = false; diagonal = false }
+;;
+
+external sort_by_exprs
+  :  t
+  -> expr array
+  -> bool array
+  -> sort_multiple_options
+  -> t
+  = "ml_lazy_sort"
+
+(* [descending] and [nulls_last] are per sort expression, single values apply to all *)
+let sort_by_exprs
+      ?(descending = [| false |])
+      ?(maintain_order = false)
+      ?(nulls_last = [| false |])
+      ?(multithreaded = false)
+      by
+      t
+  =
+  let opts = { descending; maintain_order; nulls_last = false; multithreaded } in
+  sort_by_exprs t by nulls_last opts
+;;
 
 let sort
       ?(descending = false)
       ?(maintain_order = false)
       ?(nulls_last = false)
       ?(multithreaded = false)
-      col
+      column
       t
   =
-  let descending = [| descending |] in
-  let opts = { descending; maintain_order; nulls_last; multithreaded } in
-  sort t col opts
+  sort_by_exprs
+    ~descending:[| descending |]
+    ~maintain_order
+    ~nulls_last:[| nulls_last |]
+    ~multithreaded
+    [| col column |]
+    t
+;;
+
+external top_k : t -> int -> expr array -> sort_multiple_options -> t = "ml_lazy_top_k"
+
+external bottom_k
+  :  t
+  -> int
+  -> expr array
+  -> sort_multiple_options
+  -> t
+  = "ml_lazy_bottom_k"
+
+let top_k
+      ?(descending = [| false |])
+      ?(maintain_order = false)
+      ?(nulls_last = false)
+      ?(multithreaded = false)
+      k
+      by
+      t
+  =
+  top_k t k by { descending; maintain_order; nulls_last; multithreaded }
+;;
+
+let bottom_k
+      ?(descending = [| false |])
+      ?(maintain_order = false)
+      ?(nulls_last = false)
+      ?(multithreaded = false)
+      k
+      by
+      t
+  =
+  bottom_k t k by { descending; maintain_order; nulls_last; multithreaded }
 ;;
 
 external select : t -> expr array -> t = "ml_lazy_select"
 
 let select exprs t = select t exprs
 
-external collect : t -> Polars.t = "ml_lazy_collect"
+external limit : t -> int -> t = "ml_lazy_limit"
+
+let limit n t = limit t n
+
+external slice : t -> int -> int -> t = "ml_lazy_slice"
+
+let slice ~offset ~len t = slice t offset len
+
+external tail : t -> int -> t = "ml_lazy_tail"
+
+let tail n t = tail t n
+
+external unique
+  :  t
+  -> string array option
+  -> unique_keep_strategy
+  -> bool
+  -> t
+  = "ml_lazy_unique"
+
+let unique ?subset ?(keep = (Any : unique_keep_strategy)) ?(maintain_order = false) t =
+  unique t subset keep maintain_order
+;;
+
+external drop_nulls : t -> expr array option -> t = "ml_lazy_drop_nulls"
+
+let drop_nulls ?subset t = drop_nulls t subset
+
+(* named [fill_nulls] so it does not shadow the [fill_null] expression builder *)
+external fill_nulls : t -> expr -> t = "ml_lazy_fill_null"
+
+let fill_nulls ~with_ t = fill_nulls t with_
+
+external fill_nulls_with_strategy
+  :  t
+  -> fill_null_strategy
+  -> t
+  = "ml_lazy_fill_null_with_strategy"
+
+let fill_nulls_with_strategy strategy t = fill_nulls_with_strategy t strategy
+
+external rename : t -> string array -> string array -> t = "ml_lazy_rename"
+
+let rename ~existing ~new_ t = rename t existing new_
+
+external drop : t -> string array -> t = "ml_lazy_drop"
+
+let drop columns t = drop t columns
+
+external reverse : t -> t = "ml_lazy_reverse"
+external shift : t -> int -> expr option -> t = "ml_lazy_shift"
+
+let shift ?fill_value periods t = shift t periods fill_value
+
+(* resolves the output schema without executing the plan *)
+external schema : t -> Schema.t = "ml_lazy_schema"
+
+external explain : t -> bool -> string = "ml_lazy_explain"
+
+let explain ?(optimized = true) t = explain t optimized
+
+external to_dot : t -> bool -> string = "ml_lazy_to_dot"
+
+let to_dot ?(optimized = true) t = to_dot t optimized
+
+external with_optimization : t -> optimization -> bool -> t = "ml_lazy_with_optimization"
+
+let with_optimization optimization toggle t = with_optimization t optimization toggle
+let with_predicate_pushdown = with_optimization PredicatePushdown
+let with_projection_pushdown = with_optimization ProjectionPushdown
+let with_slice_pushdown = with_optimization SlicePushdown
+let with_comm_subplan_elim = with_optimization CommSubplanElim
+let with_comm_subexpr_elim = with_optimization CommSubexprElim
+let with_type_coercion = with_optimization TypeCoercion
+let with_simplify_expr = with_optimization SimplifyExpr
+
+external collect : t -> bool -> Polars.t = "ml_lazy_collect"
+
+(* with [streaming] nodes that support it run on the streaming engine *)
+let collect ?(streaming = false) t = collect t streaming
+
+external collect_all : t array -> bool -> Polars.t array = "ml_lazy_collect_all"
+
+(* collects all frames in parallel, subplans shared between frames are computed once *)
+let collect_all ?(streaming = false) ts = collect_all ts streaming
+
+(* returns the result and a dataframe with [node], [start] and [end] in microseconds *)
+external profile : t -> Polars.t * Polars.t = "ml_lazy_profile"
+
+(* streaming sinks *)
+(* [f] is called with each batch as it is produced, and may stop the query by returning
+   [Stop] *)
+external sink_batches : t -> (Polars.t -> sink_control) -> unit = "ml_lazy_sink_batches"
+
+let sink_batches f t = sink_batches t f
+
+external sink_parquet
+  :  t
+  -> string
+  -> parquet_write_options
+  -> unit
+  = "ml_lazy_sink_parquet"
+
+let sink_parquet
+      ?(compression = (Zstd : parquet_compression))
+      ?(statistics = false)
+      ?row_group_size
+      ?data_pagesize_limit
+      ?(maintain_order = true)
+      path
+      t
+  =
+  let opts =
+    { compression; statistics; row_group_size; data_pagesize_limit; maintain_order }
+  in
+  sink_parquet t path opts
+;;
+
+external sink_ipc : t -> string -> ipc_writer_options -> unit = "ml_lazy_sink_ipc"
+
+let sink_ipc ?compression ?(maintain_order = true) path t =
+  sink_ipc t path { compression; maintain_order }
+;;
+
+external sink_csv : t -> string -> csv_writer_options -> unit = "ml_lazy_sink_csv"
+
+let sink_csv
+      ?(include_bom = false)
+      ?(include_header = true)
+      ?(batch_size = 1024)
+      ?(maintain_order = true)
+      ?(separator = ',')
+      ?float_precision
+      ?(null_value = "")
+      path
+      t
+  =
+  let opts =
+    { include_bom
+    ; include_header
+    ; batch_size
+    ; maintain_order
+    ; separator = Char.to_int separator
+    ; float_precision
+    ; null_value
+    }
+  in
+  sink_csv t path opts
+;;
+
+module Sql = struct
+  type context = sql_context
+
+  external create : unit -> context = "ml_sql_context_new"
+  external register : context -> string -> t -> unit = "ml_sql_context_register"
+
+  external register_df
+    :  context
+    -> string
+    -> Polars.t
+    -> unit
+    = "ml_sql_context_register_df"
+
+  external unregister : context -> string -> unit = "ml_sql_context_unregister"
+  external tables : context -> string array = "ml_sql_context_tables"
+  external execute : context -> string -> t = "ml_sql_context_execute"
+
+  let register ctx ~name t = register ctx name t
+  let register_df ctx ~name df = register_df ctx name df
+end
 
 module Eager = struct
   let select_names cols = Array.map ~f:col cols |> select
@@ -0,0 +1,46 @@
+// Copyright © 2024 Łukasz Kurowski. All rights reserved.
+// SPDX-License-Identifier: MIT
+
+use polars::prelude::IntoLazy;
+
+use crate::{deref, deref_mut, polars0::*};
+
+#[ocaml::func]
+pub fn ml_sql_context_new() -> SQLContextPtr {
+    Pointer::alloc_custom(SQLContext(pl::SQLContext::new()))
+}
+
+#[ocaml::func]
+pub fn ml_sql_context_register(mut ctx: SQLContextPtr, name: &str, df: LazyFramePtr) {
+    deref_mut!(ctx).register(name, deref!(df).clone());
+}
+
+#[ocaml::func]
+pub fn ml_sql_context_register_df(mut ctx: SQLContextPtr, name: &str, df: DataFramePtr) {
+    deref_mut!(ctx).register(name, deref!(df).clone().lazy());
+}
+
+#[ocaml::func]
+pub fn ml_sql_context_unregister(mut ctx: SQLContextPtr, name: &str) {
+    deref_mut!(ctx).unregister(name);
+}
+
+#[ocaml::func]
+pub fn ml_sql_context_tables(ctx: SQLContextPtr) -> Vec<String> {
+    deref!(ctx).get_tables()
+}
+
+#[ocaml::func]
+pub fn ml_sql_context_execute(mut ctx: SQLContextPtr, query: &str) -> Result<LazyFramePtr, Error> {
+    let df = deref_mut!(ctx)
+        .execute(query)
+        .map_err(|e| error_with_desc(e, format!("sql {}", query)))?;
+    Ok(Pointer::alloc_custom(LazyFrame(df)))
+}
+
+#[ocaml::func]
+pub fn ml_sql_expr(sql: &str) -> Result<SQLExprPtr, Error> {
+    let expr =
+        pl::sql_expr(sql).map_err(|e| error_with_desc(e, format!("sql expression {}", sql)))?;
+    Ok(Pointer::alloc_custom(SQLExpr(expr)))
+}
@@ -15,6 +15,19 @@ type data_type =
   | Float64
   | Boolean
   | Datetime of time_unit
+  | UInt32
+  | String
+  | Date
+  | Time
+  | Duration of time_unit
+  | Int8
+  | Int16
+  | Int32
+  | UInt8
+  | UInt16
+  | UInt64
+  | List of data_type
+  | Struct of (string * data_type) array
 [@@deriving compare, equal, sexp]
 
 type any_value =
@@ -23,6 +36,11 @@ type any_value =
   | Float64 of float
   | Boolean of bool
   | Datetime of int * time_unit
+  | UInt32 of int
+  | String of string
+  | Date of int
+  | Time of int
+  | Duration of int * time_unit
 [@@deriving compare, equal, sexp]
 
 type duration =
@@ -96,11 +114,86 @@ type boolean_function =
   | AllHorizontal
   | AnyHorizontal
 
+type strptime_options =
+  { format : string option
+  ; strict : bool
+  ; exact : bool
+  ; cache : bool
+  }
+
+type string_function =
+  | Contains of
+      { literal : bool
+      ; strict : bool
+      }
+  | CountMatches of bool
+  | StartsWith
+  | EndsWith
+  | Extract of int
+  | ExtractAll
+  | Replace of
+      { n : int
+      ; literal : bool
+      }
+  | Split of bool
+  | Slice
+  | Lowercase
+  | Uppercase
+  | StripChars
+  | StripCharsStart
+  | StripCharsEnd
+  | StripPrefix
+  | StripSuffix
+  | LenBytes
+  | LenChars
+  | ZFill
+  | PadStart of
+      { length : int
+      ; fill_char : int
+      }
+  | PadEnd of
+      { length : int
+      ; fill_char : int
+      }
+  | Strptime of data_type * strptime_options
+
+type pow_function =
+  | Generic
+  | Sqrt
+  | Cbrt
+
+type trigonometric_function =
+  | Cos
+  | Cot
+  | Sin
+  | Tan
+  | ArcCos
+  | ArcSin
+  | ArcTan
+  | Cosh
+  | Sinh
+  | Tanh
+  | ArcCosh
+  | ArcSinh
+  | ArcTanh
+  | Degrees
+  | Radians
+
 type function_expr =
   | Abs
   | NullCount
+  | Pow of pow_function
+  | StringExpr of string_function
+  | DateOffset
+  | Trigonometry of trigonometric_function
+  | Atan2
+  | Sign
   | FillNull
   | DropNans
+  | Clip of
+      { has_min : bool
+      ; has_max : bool
+      }
   | Shift
   | CumCount of { reverse : bool }
   | CumSum of { reverse : bool }
@@ -158,13 +251,54 @@ type closed_window =
   | Both
   | None
 
+type label =
+  | Left
+  | Right
+  | DataPoint
+
+type start_by =
+  | WindowBound
+  | DataPoint
+  | Monday
+  | Tuesday
+  | Wednesday
+  | Thursday
+  | Friday
+  | Saturday
+  | Sunday
+
+type dynamic_group_options =
+  { every : duration
+  ; period : duration
+  ; offset : duration
+  ; label : label
+  ; include_boundaries : bool
+  ; closed_window : closed_window
+  ; start_by : start_by
+  ; check_sorted : bool
+  }
+
+type rolling_group_options =
+  { period : duration
+  ; offset : duration
+  ; closed_window : closed_window
+  ; check_sorted : bool
+  }
+
+type quantile_interpol_options =
+  | Nearest
+  | Lower
+  | Higher
+  | Midpoint
+  | Linear
+
 type rolling =
   | Min
   | Max
   | Mean
   | Sum
   | Median
-  | Quantile of float
+  | Quantile of float * quantile_interpol_options
   | Var
   | Std
 
@@ -182,7 +316,63 @@ type horizontal =
   | Max
   | Sum
 
-type expr =
+type temporal_function =
+  | Year
+  | Month
+  | Day
+  | WeekDay
+  | OrdinalDay
+  | Hour
+  | Minute
+  | Second
+  | Millisecond
+  | Microsecond
+  | Nanosecond
+  | ToString of string
+  | CastTimeUnit of time_unit
+  | WithTimeUnit of time_unit
+  | TimeStamp of time_unit
+  | Truncate of
+      { every : expr
+      ; offset : duration
+      }
+  | Round of
+      { every : expr
+      ; offset : duration
+      }
+  | MonthStart
+  | MonthEnd
+
+and list_function =
+  | Length
+  | Get of
+      { index : expr
+      ; null_on_oob : bool
+      }
+  | Gather of
+      { index : expr
+      ; null_on_oob : bool
+      }
+  | Slice of
+      { offset : expr
+      ; length : expr
+      }
+  | Sum
+  | Mean
+  | Min
+  | Max
+  | Contains of expr
+  | Unique of bool
+  | Sort of
+      { descending : bool
+      ; nulls_last : bool
+      }
+  | Join of
+      { separator : expr
+      ; ignore_nulls : bool
+      }
+
+and expr =
   | Alias of expr * string
   | Column of string
   | Columns of string array
@@ -248,6 +438,49 @@ type expr =
       ; op : horizontal
       }
   | ForwardFill of expr * int option
+  | MapBatches of
+      { input : expr
+      ; function_ : Polars0.series_udf
+      ; output_type : data_type
+      }
+  | Sql of Polars0.sql_expr
+  | DateRange of
+      { start : expr
+      ; end_ : expr
+      ; interval : duration
+      ; closed_window : closed_window
+      }
+  | DatetimeRange of
+      { start : expr
+      ; end_ : expr
+      ; interval : duration
+      ; closed_window : closed_window
+      ; time_unit : time_unit option
+      ; time_zone : string option
+      }
+  | TimeRange of
+      { start : expr
+      ; end_ : expr
+      ; interval : duration
+      ; closed_window : closed_window
+      }
+  | ListEval of
+      { input : expr
+      ; expr : expr
+      ; parallel : bool
+      }
+  | ListToStruct of
+      { input : expr
+      ; upper_bound : int
+      }
+  | TemporalExpr of
+      { input : expr
+      ; function_ : temporal_function
+      }
+  | ListExpr of
+      { input : expr
+      ; function_ : list_function
+      }
 
 and agg_expr =
   | Min of
@@ -265,6 +498,11 @@ and agg_expr =
   | Mean of expr
   | Implode of expr
   | Count of expr * bool
+  | Quantile of
+      { expr : expr
+      ; quantile : expr
+      ; interpol : quantile_interpol_options
+      }
   | Sum of expr
   | AggGroups of expr
   | Std of expr * int
@@ -277,3 +515,126 @@ type read_csv =
   ; schema : (string * data_type) array option
   ; n_threads : int
   }
+
+type parquet_compression =
+  | Uncompressed
+  | Snappy
+  | Gzip
+  | Lzo
+  | Brotli
+  | Zstd
+  | Lz4Raw
+
+type parquet_write_options =
+  { compression : parquet_compression
+  ; statistics : bool
+  ; row_group_size : int option
+  ; data_pagesize_limit : int option
+  ; maintain_order : bool
+  }
+
+type ipc_compression =
+  | Lz4
+  | Zstd
+
+type ipc_writer_options =
+  { compression : ipc_compression option
+  ; maintain_order : bool
+  }
+
+type csv_writer_options =
+  { include_bom : bool
+  ; include_header : bool
+  ; batch_size : int
+  ; maintain_order : bool
+  ; separator : int
+  ; float_precision : int option
+  ; null_value : string
+  }
+
+type partition_file_format =
+  | Parquet
+  | Ipc
+
+type partition_write_mode =
+  | Overwrite
+  | Append
+
+type write_partitioned =
+  { partition_by : string array
+  ; format : partition_file_format
+  ; max_rows_per_file : int option
+  ; mode : partition_write_mode
+  ; include_key : bool
+  }
+
+type join_type =
+  | Left
+  | Inner
+  | Outer
+  | Cross
+  | Semi
+  | Anti
+
+type join_validation =
+  | ManyToMany
+  | ManyToOne
+  | OneToMany
+  | OneToOne
+
+type join_args =
+  { how : join_type
+  ; validation : join_validation
+  ; suffix : string option
+  ; join_nulls : bool
+  }
+
+type asof_strategy =
+  | Backward
+  | Forward
+  | Nearest
+
+type as_of_options =
+  { strategy : asof_strategy
+  ; tolerance : duration option
+  ; left_by : string array option
+  ; right_by : string array option
+  ; suffix : string option
+  }
+
+type unique_keep_strategy =
+  | First
+  | Last
+  | Keep_none
+  | Any
+
+type fill_null_strategy =
+  | Backward of int option
+  | Forward of int option
+  | Mean
+  | Min
+  | Max
+  | Zero
+  | One
+  | MaxBound
+  | MinBound
+
+type union_args =
+  { parallel : bool
+  ; rechunk : bool
+  ; to_supertypes : bool
+  ; diagonal : bool
+  }
+
+type optimization =
+  | PredicatePushdown
+  | ProjectionPushdown
+  | SlicePushdown
+  | CommSubplanElim
+  | CommSubexprElim
+  | TypeCoercion
+  | SimplifyExpr
+
+type sink_control =
+  | Continue
+  | Stop
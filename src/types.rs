@@ -5,7 +5,7 @@ use std::sync::Arc;
 
 pub use crate::polars0::*;
 use ocamlrep_derive::ToOcamlRep;
-use polars::prelude::Literal;
+use polars::prelude::{ListNameSpaceExtension, Literal};
 
 #[derive(Debug, Copy, Clone, FromValue, ToValue, ToOcamlRep)]
 #[rust_to_ocaml(attr = "deriving compare, equal, sexp")]
@@ -43,6 +43,11 @@ pub enum AnyValue {
     Float64(f64),
     Boolean(bool),
     Datetime(isize, TimeUnit),
+    UInt32(u32),
+    String(String),
+    Date(isize),
+    Time(isize),
+    Duration(isize, TimeUnit),
 }
 
 impl TryFrom<pl::AnyValue<'_>> for AnyValue {
@@ -55,6 +60,12 @@ impl TryFrom<pl::AnyValue<'_>> for AnyValue {
             pl::AnyValue::Float64(v) => Ok(AnyValue::Float64(v)),
             pl::AnyValue::Boolean(v) => Ok(AnyValue::Boolean(v)),
             pl::AnyValue::Datetime(v, unit, _) => Ok(AnyValue::Datetime(v as isize, unit.into())),
+            pl::AnyValue::UInt32(v) => Ok(AnyValue::UInt32(v)),
+            pl::AnyValue::String(v) => Ok(AnyValue::String(v.to_string())),
+            pl::AnyValue::StringOwned(v) => Ok(AnyValue::String(v.to_string())),
+            pl::AnyValue::Date(v) => Ok(AnyValue::Date(v as isize)),
+            pl::AnyValue::Time(v) => Ok(AnyValue::Time(v as isize)),
+            pl::AnyValue::Duration(v, unit) => Ok(AnyValue::Duration(v as isize, unit.into())),
             dtype => Err(error_msg(format!(
                 "AnyValue for dtype {:?} not implemented",
                 dtype
@@ -63,7 +74,7 @@ impl TryFrom<pl::AnyValue<'_>> for AnyValue {
     }
 }
 
-#[derive(Debug, Copy, Clone, FromValue, ToValue, ToOcamlRep)]
+#[derive(Debug, Clone, FromValue, ToValue, ToOcamlRep)]
 #[rust_to_ocaml(attr = "deriving compare, equal, sexp")]
 pub enum DataType {
     Int64,
@@ -71,6 +82,19 @@ pub enum DataType {
     Float64,
     Boolean,
     Datetime(TimeUnit),
+    UInt32,
+    String,
+    Date,
+    Time,
+    Duration(TimeUnit),
+    Int8,
+    Int16,
+    Int32,
+    UInt8,
+    UInt16,
+    UInt64,
+    List(Box<DataType>),
+    Struct(Vec<(String, DataType)>),
 }
 
 impl From<&DataType> for pl::DataType {
@@ -81,6 +105,24 @@ impl From<&DataType> for pl::DataType {
             DataType::Float64 => pl::DataType::Float64,
             DataType::Boolean => pl::DataType::Boolean,
             DataType::Datetime(tu) => pl::DataType::Datetime(tu.into(), None),
+            DataType::UInt32 => pl::DataType::UInt32,
+            DataType::String => pl::DataType::String,
+            DataType::Date => pl::DataType::Date,
+            DataType::Time => pl::DataType::Time,
+            DataType::Duration(tu) => pl::DataType::Duration(tu.into()),
+            DataType::Int8 => pl::DataType::Int8,
+            DataType::Int16 => pl::DataType::Int16,
+            DataType::Int32 => pl::DataType::Int32,
+            DataType::UInt8 => pl::DataType::UInt8,
+            DataType::UInt16 => pl::DataType::UInt16,
+            DataType::UInt64 => pl::DataType::UInt64,
+            DataType::List(inner) => pl::DataType::List(Box::new(inner.as_ref().into())),
+            DataType::Struct(fields) => pl::DataType::Struct(
+                fields
+                    .iter()
+                    .map(|(name, dtype)| pl::Field::new(name, dtype.into()))
+                    .collect(),
+            ),
         }
     }
 }
@@ -95,7 +137,25 @@ impl TryFrom<&pl::DataType> for DataType {
             pl::DataType::Float64 => Ok(DataType::Float64),
             pl::DataType::Boolean => Ok(DataType::Boolean),
             pl::DataType::Datetime(tu, _) => Ok(DataType::Datetime(TimeUnit::from(*tu))),
-            _ => Err(Error::Message("dtype not supported")),
+            pl::DataType::UInt32 => Ok(DataType::UInt32),
+            pl::DataType::String => Ok(DataType::String),
+            pl::DataType::Date => Ok(DataType::Date),
+            pl::DataType::Time => Ok(DataType::Time),
+            pl::DataType::Duration(tu) => Ok(DataType::Duration(TimeUnit::from(*tu))),
+            pl::DataType::Int8 => Ok(DataType::Int8),
+            pl::DataType::Int16 => Ok(DataType::Int16),
+            pl::DataType::Int32 => Ok(DataType::Int32),
+            pl::DataType::UInt8 => Ok(DataType::UInt8),
+            pl::DataType::UInt16 => Ok(DataType::UInt16),
+            pl::DataType::UInt64 => Ok(DataType::UInt64),
+            pl::DataType::List(inner) => Ok(DataType::List(Box::new(inner.as_ref().try_into()?))),
+            pl::DataType::Struct(fields) => Ok(DataType::Struct(
+                fields
+                    .iter()
+                    .map(|field| Ok((field.name().to_string(), field.data_type().try_into()?)))
+                    .collect::<Result<_, Error>>()?,
+            )),
+            dtype => Err(error_msg(format!("dtype {} not supported", dtype))),
         }
     }
 }
@@ -116,6 +176,16 @@ impl From<&Duration> for pl::Duration {
     }
 }
 
+impl Duration {
+    /// Duration string as accepted by temporal functions, e.g. `1h30m`.
+    fn to_polars_string(&self) -> String {
+        match self {
+            Duration::Slots(s) => format!("{}ns", s),
+            Duration::Duration(s) => s.clone(),
+        }
+    }
+}
+
 #[derive(FromValue, ToOcamlRep)]
 pub enum IsSorted {
     Ascending,
@@ -167,6 +237,13 @@ impl Into<pl::Expr> for AnyValue {
             AnyValue::Datetime(v, unit) => {
                 pl::LiteralValue::DateTime(v as i64, pl::TimeUnit::from(&unit), None).lit()
             }
+            AnyValue::UInt32(v) => v.lit(),
+            AnyValue::String(v) => v.lit(),
+            AnyValue::Date(v) => pl::LiteralValue::Date(v as i32).lit(),
+            AnyValue::Time(v) => pl::LiteralValue::Time(v as i64).lit(),
+            AnyValue::Duration(v, unit) => {
+                pl::LiteralValue::Duration(v as i64, pl::TimeUnit::from(&unit)).lit()
+            }
         }
     }
 }
@@ -332,27 +409,169 @@ impl From<&BooleanFunction> for pl::BooleanFunction {
     }
 }
 
+#[derive(FromValue, ToOcamlRep)]
+pub struct StrptimeOptions {
+    pub format: Option<String>,
+    pub strict: bool,
+    pub exact: bool,
+    pub cache: bool,
+}
+
+impl From<&StrptimeOptions> for pl::StrptimeOptions {
+    fn from(value: &StrptimeOptions) -> Self {
+        pl::StrptimeOptions {
+            format: value.format.clone(),
+            strict: value.strict,
+            exact: value.exact,
+            cache: value.cache,
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum StringFunction {
+    Contains { literal: bool, strict: bool },
+    CountMatches(bool),
+    StartsWith,
+    EndsWith,
+    Extract(usize),
+    ExtractAll,
+    Replace { n: isize, literal: bool },
+    Split(bool),
+    Slice,
+    Lowercase,
+    Uppercase,
+    StripChars,
+    StripCharsStart,
+    StripCharsEnd,
+    StripPrefix,
+    StripSuffix,
+    LenBytes,
+    LenChars,
+    ZFill,
+    PadStart { length: usize, fill_char: u8 },
+    PadEnd { length: usize, fill_char: u8 },
+    Strptime(DataType, StrptimeOptions),
+}
+
+impl From<&StringFunction> for pl::StringFunction {
+    fn from(value: &StringFunction) -> Self {
+        match value {
+            StringFunction::Contains { literal, strict } => pl::StringFunction::Contains {
+                literal: *literal,
+                strict: *strict,
+            },
+            StringFunction::CountMatches(literal) => pl::StringFunction::CountMatches(*literal),
+            StringFunction::StartsWith => pl::StringFunction::StartsWith,
+            StringFunction::EndsWith => pl::StringFunction::EndsWith,
+            StringFunction::Extract(group_index) => pl::StringFunction::Extract(*group_index),
+            StringFunction::ExtractAll => pl::StringFunction::ExtractAll,
+            StringFunction::Replace { n, literal } => pl::StringFunction::Replace {
+                n: *n as i64,
+                literal: *literal,
+            },
+            StringFunction::Split(inclusive) => pl::StringFunction::Split(*inclusive),
+            StringFunction::Slice => pl::StringFunction::Slice,
+            StringFunction::Lowercase => pl::StringFunction::Lowercase,
+            StringFunction::Uppercase => pl::StringFunction::Uppercase,
+            StringFunction::StripChars => pl::StringFunction::StripChars,
+            StringFunction::StripCharsStart => pl::StringFunction::StripCharsStart,
+            StringFunction::StripCharsEnd => pl::StringFunction::StripCharsEnd,
+            StringFunction::StripPrefix => pl::StringFunction::StripPrefix,
+            StringFunction::StripSuffix => pl::StringFunction::StripSuffix,
+            StringFunction::LenBytes => pl::StringFunction::LenBytes,
+            StringFunction::LenChars => pl::StringFunction::LenChars,
+            StringFunction::ZFill => pl::StringFunction::ZFill,
+            StringFunction::PadStart { length, fill_char } => pl::StringFunction::PadStart {
+                length: *length,
+                fill_char: *fill_char as char,
+            },
+            StringFunction::PadEnd { length, fill_char } => pl::StringFunction::PadEnd {
+                length: *length,
+                fill_char: *fill_char as char,
+            },
+            StringFunction::Strptime(dtype, options) => {
+                pl::StringFunction::Strptime(dtype.into(), options.into())
+            }
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum PowFunction {
+    Generic,
+    Sqrt,
+    Cbrt,
+}
+
+impl From<&PowFunction> for pl::PowFunction {
+    fn from(value: &PowFunction) -> Self {
+        match value {
+            PowFunction::Generic => pl::PowFunction::Generic,
+            PowFunction::Sqrt => pl::PowFunction::Sqrt,
+            PowFunction::Cbrt => pl::PowFunction::Cbrt,
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum TrigonometricFunction {
+    Cos,
+    Cot,
+    Sin,
+    Tan,
+    ArcCos,
+    ArcSin,
+    ArcTan,
+    Cosh,
+    Sinh,
+    Tanh,
+    ArcCosh,
+    ArcSinh,
+    ArcTanh,
+    Degrees,
+    Radians,
+}
+
+impl From<&TrigonometricFunction> for pl::TrigonometricFunction {
+    fn from(value: &TrigonometricFunction) -> Self {
+        match value {
+            TrigonometricFunction::Cos => pl::TrigonometricFunction::Cos,
+            TrigonometricFunction::Cot => pl::TrigonometricFunction::Cot,
+            TrigonometricFunction::Sin => pl::TrigonometricFunction::Sin,
+            TrigonometricFunction::Tan => pl::TrigonometricFunction::Tan,
+            TrigonometricFunction::ArcCos => pl::TrigonometricFunction::ArcCos,
+            TrigonometricFunction::ArcSin => pl::TrigonometricFunction::ArcSin,
+            TrigonometricFunction::ArcTan => pl::TrigonometricFunction::ArcTan,
+            TrigonometricFunction::Cosh => pl::TrigonometricFunction::Cosh,
+            TrigonometricFunction::Sinh => pl::TrigonometricFunction::Sinh,
+            TrigonometricFunction::Tanh => pl::TrigonometricFunction::Tanh,
+            TrigonometricFunction::ArcCosh => pl::TrigonometricFunction::ArcCosh,
+            TrigonometricFunction::ArcSinh => pl::TrigonometricFunction::ArcSinh,
+            TrigonometricFunction::ArcTanh => pl::TrigonometricFunction::ArcTanh,
+            TrigonometricFunction::Degrees => pl::TrigonometricFunction::Degrees,
+            TrigonometricFunction::Radians => pl::TrigonometricFunction::Radians,
+        }
+    }
+}
+
 #[derive(FromValue, ToOcamlRep)]
 pub enum FunctionExpr {
     Abs,
     NullCount,
-    // Pow(PowFunction),
-    // StringExpr(StringFunction),
+    Pow(PowFunction),
+    StringExpr(StringFunction),
     // BinaryExpr(BinaryFunction),
-    // TODO:
-    // TemporalExpr(TemporalFunction),
-    // Trigonometry(TrigonometricFunction),
-    // Atan2,
+    DateOffset,
+    Trigonometry(TrigonometricFunction),
+    Atan2,
+    Sign,
     FillNull,
     // ShiftAndFill {
     //     periods: isize,
     // },
     DropNans,
-    // Clip {
-    //     min: Option<pl::AnyValue<'static>>,
-    //     max: Option<pl::AnyValue<'static>>,
-    // },
-    // ListExpr(ListFunction),
+    Clip { has_min: bool, has_max: bool },
     Shift,
     CumCount { reverse: bool },
     CumSum { reverse: bool },
@@ -384,10 +603,24 @@ impl From<&FunctionExpr> for pl::FunctionExpr {
         match value {
             FunctionExpr::Abs => pl::FunctionExpr::Abs,
             FunctionExpr::NullCount => pl::FunctionExpr::NullCount,
+            FunctionExpr::Pow(pow_func) => pl::FunctionExpr::Pow(pow_func.into()),
+            FunctionExpr::StringExpr(string_func) => {
+                pl::FunctionExpr::StringExpr(string_func.into())
+            }
+            FunctionExpr::Trigonometry(trig_func) => {
+                pl::FunctionExpr::Trigonometry(trig_func.into())
+            }
+            FunctionExpr::Atan2 => pl::FunctionExpr::Atan2,
+            FunctionExpr::Sign => pl::FunctionExpr::Sign,
+            FunctionExpr::DateOffset => pl::FunctionExpr::DateOffset,
             FunctionExpr::FillNull => pl::FunctionExpr::FillNull {
                 super_type: pl::DataType::Unknown,
             },
             FunctionExpr::DropNans => pl::FunctionExpr::DropNans,
+            FunctionExpr::Clip { has_min, has_max } => pl::FunctionExpr::Clip {
+                has_min: *has_min,
+                has_max: *has_max,
+            },
             FunctionExpr::Shift => pl::FunctionExpr::Shift,
             FunctionExpr::CumCount { reverse } => pl::FunctionExpr::CumCount { reverse: *reverse },
             FunctionExpr::CumSum { reverse } => pl::FunctionExpr::CumSum { reverse: *reverse },
@@ -514,6 +747,121 @@ impl From<&ClosedWindow> for pl::ClosedWindow {
     }
 }
 
+#[derive(FromValue, ToOcamlRep)]
+pub enum Label {
+    Left,
+    Right,
+    DataPoint,
+}
+
+impl From<&Label> for pl::Label {
+    fn from(value: &Label) -> Self {
+        match value {
+            Label::Left => pl::Label::Left,
+            Label::Right => pl::Label::Right,
+            Label::DataPoint => pl::Label::DataPoint,
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum StartBy {
+    WindowBound,
+    DataPoint,
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl From<&StartBy> for pl::StartBy {
+    fn from(value: &StartBy) -> Self {
+        match value {
+            StartBy::WindowBound => pl::StartBy::WindowBound,
+            StartBy::DataPoint => pl::StartBy::DataPoint,
+            StartBy::Monday => pl::StartBy::Monday,
+            StartBy::Tuesday => pl::StartBy::Tuesday,
+            StartBy::Wednesday => pl::StartBy::Wednesday,
+            StartBy::Thursday => pl::StartBy::Thursday,
+            StartBy::Friday => pl::StartBy::Friday,
+            StartBy::Saturday => pl::StartBy::Saturday,
+            StartBy::Sunday => pl::StartBy::Sunday,
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub struct DynamicGroupOptions {
+    pub every: Duration,
+    pub period: Duration,
+    pub offset: Duration,
+    pub label: Label,
+    pub include_boundaries: bool,
+    pub closed_window: ClosedWindow,
+    pub start_by: StartBy,
+    pub check_sorted: bool,
+}
+
+impl From<&DynamicGroupOptions> for pl::DynamicGroupOptions {
+    fn from(value: &DynamicGroupOptions) -> Self {
+        pl::DynamicGroupOptions {
+            every: pl::Duration::from(&value.every),
+            period: pl::Duration::from(&value.period),
+            offset: pl::Duration::from(&value.offset),
+            label: pl::Label::from(&value.label),
+            include_boundaries: value.include_boundaries,
+            closed_window: pl::ClosedWindow::from(&value.closed_window),
+            start_by: pl::StartBy::from(&value.start_by),
+            check_sorted: value.check_sorted,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub struct RollingGroupOptions {
+    pub period: Duration,
+    pub offset: Duration,
+    pub closed_window: ClosedWindow,
+    pub check_sorted: bool,
+}
+
+impl From<&RollingGroupOptions> for pl::RollingGroupOptions {
+    fn from(value: &RollingGroupOptions) -> Self {
+        pl::RollingGroupOptions {
+            period: pl::Duration::from(&value.period),
+            offset: pl::Duration::from(&value.offset),
+            closed_window: pl::ClosedWindow::from(&value.closed_window),
+            check_sorted: value.check_sorted,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum QuantileInterpolOptions {
+    Nearest,
+    Lower,
+    Higher,
+    Midpoint,
+    Linear,
+}
+
+impl From<&QuantileInterpolOptions> for pl::QuantileInterpolOptions {
+    fn from(value: &QuantileInterpolOptions) -> Self {
+        match value {
+            QuantileInterpolOptions::Nearest => pl::QuantileInterpolOptions::Nearest,
+            QuantileInterpolOptions::Lower => pl::QuantileInterpolOptions::Lower,
+            QuantileInterpolOptions::Higher => pl::QuantileInterpolOptions::Higher,
+            QuantileInterpolOptions::Midpoint => pl::QuantileInterpolOptions::Midpoint,
+            QuantileInterpolOptions::Linear => pl::QuantileInterpolOptions::Linear,
+        }
+    }
+}
+
 #[derive(FromValue, ToOcamlRep)]
 pub enum Rolling {
     Min,
@@ -521,8 +869,7 @@ pub enum Rolling {
     Mean,
     Sum,
     Median,
-    // TODO: QuantileInterpolOptions
-    Quantile(f64),
+    Quantile(f64, QuantileInterpolOptions),
     Var,
     Std,
 }
@@ -559,6 +906,134 @@ pub enum Horizontal {
 }
 
 #[derive(FromValue, ToOcamlRep)]
+pub enum TemporalFunction {
+    Year,
+    Month,
+    Day,
+    WeekDay,
+    OrdinalDay,
+    Hour,
+    Minute,
+    Second,
+    Millisecond,
+    Microsecond,
+    Nanosecond,
+    ToString(String),
+    CastTimeUnit(TimeUnit),
+    WithTimeUnit(TimeUnit),
+    TimeStamp(TimeUnit),
+    Truncate { every: Box<Expr>, offset: Duration },
+    Round { every: Box<Expr>, offset: Duration },
+    MonthStart,
+    MonthEnd,
+}
+
+// `pl::TemporalFunction` is private to polars-plan, so temporal functions are
+// built through the `dt` namespace instead.
+impl TemporalFunction {
+    fn apply(&self, input: pl::Expr) -> pl::Expr {
+        let dt = input.dt();
+        match self {
+            TemporalFunction::Year => dt.year(),
+            TemporalFunction::Month => dt.month(),
+            TemporalFunction::Day => dt.day(),
+            TemporalFunction::WeekDay => dt.weekday(),
+            TemporalFunction::OrdinalDay => dt.ordinal_day(),
+            TemporalFunction::Hour => dt.hour(),
+            TemporalFunction::Minute => dt.minute(),
+            TemporalFunction::Second => dt.second(),
+            TemporalFunction::Millisecond => dt.millisecond(),
+            TemporalFunction::Microsecond => dt.microsecond(),
+            TemporalFunction::Nanosecond => dt.nanosecond(),
+            TemporalFunction::ToString(format) => dt.to_string(format),
+            TemporalFunction::CastTimeUnit(tu) => dt.cast_time_unit(tu.into()),
+            TemporalFunction::WithTimeUnit(tu) => dt.with_time_unit(tu.into()),
+            TemporalFunction::TimeStamp(tu) => dt.timestamp(tu.into()),
+            TemporalFunction::Truncate { every, offset } => {
+                dt.truncate(every.deref().into(), offset.to_polars_string())
+            }
+            TemporalFunction::Round { every, offset } => {
+                dt.round(every.deref().into(), offset.to_polars_string())
+            }
+            TemporalFunction::MonthStart => dt.month_start(),
+            TemporalFunction::MonthEnd => dt.month_end(),
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+#[rust_to_ocaml(and)]
+pub enum ListFunction {
+    Length,
+    Get {
+        index: Box<Expr>,
+        null_on_oob: bool,
+    },
+    Gather {
+        index: Box<Expr>,
+        null_on_oob: bool,
+    },
+    Slice {
+        offset: Box<Expr>,
+        length: Box<Expr>,
+    },
+    Sum,
+    Mean,
+    Min,
+    Max,
+    Contains(Box<Expr>),
+    Unique(bool),
+    Sort {
+        descending: bool,
+        nulls_last: bool,
+    },
+    Join {
+        separator: Box<Expr>,
+        ignore_nulls: bool,
+    },
+}
+
+// `pl::ListFunction` is private to polars-plan, so list functions are built
+// through the `list` namespace instead.
+impl ListFunction {
+    fn apply(&self, input: pl::Expr) -> pl::Expr {
+        let list = input.list();
+        match self {
+            ListFunction::Length => list.len(),
+            ListFunction::Get { index, null_on_oob } => {
+                list.get(index.deref().into(), *null_on_oob)
+            }
+            ListFunction::Gather { index, null_on_oob } => {
+                list.gather(index.deref().into(), *null_on_oob)
+            }
+            ListFunction::Slice { offset, length } => {
+                list.slice(offset.deref().into(), length.deref().into())
+            }
+            ListFunction::Sum => list.sum(),
+            ListFunction::Mean => list.mean(),
+            ListFunction::Min => list.min(),
+            ListFunction::Max => list.max(),
+            ListFunction::Contains(item) => list.contains(item.deref().into()),
+            ListFunction::Unique(true) => list.unique_stable(),
+            ListFunction::Unique(false) => list.unique(),
+            ListFunction::Sort {
+                descending,
+                nulls_last,
+            } => list.sort(pl::SortOptions {
+                descending: *descending,
+                nulls_last: *nulls_last,
+                ..Default::default()
+            }),
+            ListFunction::Join {
+                separator,
+                ignore_nulls,
+            } => list.join(separator.deref().into(), *ignore_nulls),
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+#[rust_to_ocaml(and)]
 pub enum Expr {
     Alias(Box<Expr>, String),
     Column(String),
@@ -639,6 +1114,49 @@ pub enum Expr {
         op: Horizontal,
     },
     ForwardFill(Box<Expr>, Option<u32>),
+    MapBatches {
+        input: Box<Expr>,
+        function: SeriesUdf,
+        output_type: DataType,
+    },
+    Sql(ParsedSqlExpr),
+    DateRange {
+        start: Box<Expr>,
+        end: Box<Expr>,
+        interval: Duration,
+        closed_window: ClosedWindow,
+    },
+    DatetimeRange {
+        start: Box<Expr>,
+        end: Box<Expr>,
+        interval: Duration,
+        closed_window: ClosedWindow,
+        time_unit: Option<TimeUnit>,
+        time_zone: Option<String>,
+    },
+    TimeRange {
+        start: Box<Expr>,
+        end: Box<Expr>,
+        interval: Duration,
+        closed_window: ClosedWindow,
+    },
+    ListEval {
+        input: Box<Expr>,
+        expr: Box<Expr>,
+        parallel: bool,
+    },
+    ListToStruct {
+        input: Box<Expr>,
+        upper_bound: usize,
+    },
+    TemporalExpr {
+        input: Box<Expr>,
+        function: TemporalFunction,
+    },
+    ListExpr {
+        input: Box<Expr>,
+        function: ListFunction,
+    },
 }
 
 fn arc_expr(e: &Box<Expr>) -> Arc<pl::Expr> {
@@ -747,11 +1265,9 @@ impl From<&Expr> for pl::Expr {
                     Rolling::Mean => expr.rolling_mean(opts.into()),
                     Rolling::Sum => expr.rolling_sum(opts.into()),
                     Rolling::Median => expr.rolling_median(opts.into()),
-                    Rolling::Quantile(quantile) => expr.rolling_quantile(
-                        pl::QuantileInterpolOptions::Nearest,
-                        *quantile,
-                        opts.into(),
-                    ),
+                    Rolling::Quantile(quantile, interpol) => {
+                        expr.rolling_quantile(interpol.into(), *quantile, opts.into())
+                    }
                     Rolling::Var => expr.rolling_var(opts.into()),
                     Rolling::Std => expr.rolling_std(opts.into()),
                 }
@@ -765,6 +1281,73 @@ impl From<&Expr> for pl::Expr {
                 }
             }
             Expr::ForwardFill(expr, limit) => pl::Expr::from(expr.deref()).forward_fill(*limit),
+            Expr::MapBatches {
+                input,
+                function,
+                output_type,
+            } => {
+                let function = function.clone();
+                pl::Expr::from(input.deref()).map(
+                    move |series| function.call(series).map(Some),
+                    pl::GetOutput::from_type(output_type.into()),
+                )
+            }
+            Expr::Sql(ParsedSqlExpr(expr)) => expr.as_ref().0.clone(),
+            Expr::DateRange {
+                start,
+                end,
+                interval,
+                closed_window,
+            } => pl::date_range(
+                start.deref().into(),
+                end.deref().into(),
+                pl::Duration::from(interval),
+                pl::ClosedWindow::from(closed_window),
+                None,
+                None,
+            ),
+            Expr::DatetimeRange {
+                start,
+                end,
+                interval,
+                closed_window,
+                time_unit,
+                time_zone,
+            } => pl::datetime_range(
+                start.deref().into(),
+                end.deref().into(),
+                pl::Duration::from(interval),
+                pl::ClosedWindow::from(closed_window),
+                time_unit.as_ref().map(pl::TimeUnit::from),
+                time_zone.clone(),
+            ),
+            Expr::TimeRange {
+                start,
+                end,
+                interval,
+                closed_window,
+            } => pl::time_range(
+                start.deref().into(),
+                end.deref().into(),
+                pl::Duration::from(interval),
+                pl::ClosedWindow::from(closed_window),
+            ),
+            Expr::ListEval {
+                input,
+                expr,
+                parallel,
+            } => pl::Expr::from(input.deref())
+                .list()
+                .eval(expr.deref().into(), *parallel),
+            Expr::ListToStruct { input, upper_bound } => {
+                pl::Expr::from(input.deref()).list().to_struct(
+                    pl::ListToStructWidthStrategy::FirstNonNull,
+                    None,
+                    *upper_bound,
+                )
+            }
+            Expr::TemporalExpr { input, function } => function.apply(input.deref().into()),
+            Expr::ListExpr { input, function } => function.apply(input.deref().into()),
         }
     }
 }
@@ -789,11 +1372,11 @@ pub enum AggExpr {
     Implode(Box<Expr>),
     // include_nulls
     Count(Box<Expr>, bool),
-    // Quantile {
-    //     expr: Box<Expr>,
-    //     quantile: Box<Expr>,
-    //     interpol: QuantileInterpolOptions,
-    // },
+    Quantile {
+        expr: Box<Expr>,
+        quantile: Box<Expr>,
+        interpol: QuantileInterpolOptions,
+    },
     Sum(Box<Expr>),
     AggGroups(Box<Expr>),
     Std(Box<Expr>, isize),
@@ -826,6 +1409,15 @@ impl From<&AggExpr> for pl::AggExpr {
             AggExpr::Count(expr, include_nulls) => {
                 pl::AggExpr::Count(arc_expr(expr), *include_nulls)
             }
+            AggExpr::Quantile {
+                expr,
+                quantile,
+                interpol,
+            } => pl::AggExpr::Quantile {
+                expr: arc_expr(expr),
+                quantile: arc_expr(quantile),
+                interpol: interpol.into(),
+            },
             AggExpr::Sum(expr) => pl::AggExpr::Sum(arc_expr(expr)),
             AggExpr::AggGroups(expr) => pl::AggExpr::AggGroups(arc_expr(expr)),
             AggExpr::Std(expr, ddof) => pl::AggExpr::Std(arc_expr(expr), *ddof as u8),
@@ -842,3 +1434,327 @@ pub struct ReadCsv {
     pub schema: Option<Vec<(String, DataType)>>,
     pub n_threads: usize,
 }
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum ParquetCompression {
+    Uncompressed,
+    Snappy,
+    Gzip,
+    Lzo,
+    Brotli,
+    Zstd,
+    Lz4Raw,
+}
+
+impl From<&ParquetCompression> for pl::ParquetCompression {
+    fn from(value: &ParquetCompression) -> Self {
+        match value {
+            ParquetCompression::Uncompressed => pl::ParquetCompression::Uncompressed,
+            ParquetCompression::Snappy => pl::ParquetCompression::Snappy,
+            ParquetCompression::Gzip => pl::ParquetCompression::Gzip(None),
+            ParquetCompression::Lzo => pl::ParquetCompression::Lzo,
+            ParquetCompression::Brotli => pl::ParquetCompression::Brotli(None),
+            ParquetCompression::Zstd => pl::ParquetCompression::Zstd(None),
+            ParquetCompression::Lz4Raw => pl::ParquetCompression::Lz4Raw,
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub struct ParquetWriteOptions {
+    pub compression: ParquetCompression,
+    pub statistics: bool,
+    pub row_group_size: Option<usize>,
+    pub data_pagesize_limit: Option<usize>,
+    pub maintain_order: bool,
+}
+
+impl From<&ParquetWriteOptions> for pl::ParquetWriteOptions {
+    fn from(value: &ParquetWriteOptions) -> Self {
+        pl::ParquetWriteOptions {
+            compression: pl::ParquetCompression::from(&value.compression),
+            statistics: value.statistics,
+            row_group_size: value.row_group_size,
+            data_pagesize_limit: value.data_pagesize_limit,
+            maintain_order: value.maintain_order,
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum IpcCompression {
+    Lz4,
+    Zstd,
+}
+
+impl From<&IpcCompression> for pl::IpcCompression {
+    fn from(value: &IpcCompression) -> Self {
+        match value {
+            IpcCompression::Lz4 => pl::IpcCompression::LZ4,
+            IpcCompression::Zstd => pl::IpcCompression::ZSTD,
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub struct IpcWriterOptions {
+    pub compression: Option<IpcCompression>,
+    pub maintain_order: bool,
+}
+
+impl From<&IpcWriterOptions> for pl::IpcWriterOptions {
+    fn from(value: &IpcWriterOptions) -> Self {
+        pl::IpcWriterOptions {
+            compression: value.compression.as_ref().map(pl::IpcCompression::from),
+            maintain_order: value.maintain_order,
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub struct CsvWriterOptions {
+    pub include_bom: bool,
+    pub include_header: bool,
+    pub batch_size: usize,
+    pub maintain_order: bool,
+    pub separator: u8,
+    pub float_precision: Option<usize>,
+    pub null_value: String,
+}
+
+impl From<&CsvWriterOptions> for pl::CsvWriterOptions {
+    fn from(value: &CsvWriterOptions) -> Self {
+        pl::CsvWriterOptions {
+            include_bom: value.include_bom,
+            include_header: value.include_header,
+            batch_size: std::num::NonZeroUsize::new(value.batch_size)
+                .unwrap_or(std::num::NonZeroUsize::MIN),
+            maintain_order: value.maintain_order,
+            serialize_options: pl::SerializeOptions {
+                separator: value.separator,
+                float_precision: value.float_precision,
+                null: value.null_value.clone(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum PartitionFileFormat {
+    Parquet,
+    Ipc,
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum PartitionWriteMode {
+    Overwrite,
+    Append,
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub struct WritePartitioned {
+    pub partition_by: Vec<String>,
+    pub format: PartitionFileFormat,
+    pub max_rows_per_file: Option<usize>,
+    pub mode: PartitionWriteMode,
+    pub include_key: bool,
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum JoinType {
+    Left,
+    Inner,
+    Outer,
+    Cross,
+    Semi,
+    Anti,
+}
+
+impl From<&JoinType> for pl::JoinType {
+    fn from(value: &JoinType) -> Self {
+        match value {
+            JoinType::Left => pl::JoinType::Left,
+            JoinType::Inner => pl::JoinType::Inner,
+            JoinType::Outer => pl::JoinType::Outer,
+            JoinType::Cross => pl::JoinType::Cross,
+            JoinType::Semi => pl::JoinType::Semi,
+            JoinType::Anti => pl::JoinType::Anti,
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum JoinValidation {
+    ManyToMany,
+    ManyToOne,
+    OneToMany,
+    OneToOne,
+}
+
+impl From<&JoinValidation> for pl::JoinValidation {
+    fn from(value: &JoinValidation) -> Self {
+        match value {
+            JoinValidation::ManyToMany => pl::JoinValidation::ManyToMany,
+            JoinValidation::ManyToOne => pl::JoinValidation::ManyToOne,
+            JoinValidation::OneToMany => pl::JoinValidation::OneToMany,
+            JoinValidation::OneToOne => pl::JoinValidation::OneToOne,
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub struct JoinArgs {
+    pub how: JoinType,
+    pub validation: JoinValidation,
+    pub suffix: Option<String>,
+    pub join_nulls: bool,
+}
+
+impl From<&JoinArgs> for pl::JoinArgs {
+    fn from(value: &JoinArgs) -> Self {
+        pl::JoinArgs {
+            how: pl::JoinType::from(&value.how),
+            validation: pl::JoinValidation::from(&value.validation),
+            suffix: value.suffix.clone(),
+            join_nulls: value.join_nulls,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum AsofStrategy {
+    Backward,
+    Forward,
+    Nearest,
+}
+
+impl From<&AsofStrategy> for pl::AsofStrategy {
+    fn from(value: &AsofStrategy) -> Self {
+        match value {
+            AsofStrategy::Backward => pl::AsofStrategy::Backward,
+            AsofStrategy::Forward => pl::AsofStrategy::Forward,
+            AsofStrategy::Nearest => pl::AsofStrategy::Nearest,
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub struct AsOfOptions {
+    pub strategy: AsofStrategy,
+    pub tolerance: Option<Duration>,
+    pub left_by: Option<Vec<String>>,
+    pub right_by: Option<Vec<String>>,
+    pub suffix: Option<String>,
+}
+
+/// Both sides of an as-of join must be sorted by the join key, either explicitly or by setting
+/// the sorted flag on the key series.
+impl From<&AsOfOptions> for pl::JoinArgs {
+    fn from(value: &AsOfOptions) -> Self {
+        let smart_strings = |by: &Vec<String>| by.iter().map(|s| s.as_str().into()).collect();
+        let options = pl::AsOfOptions {
+            strategy: pl::AsofStrategy::from(&value.strategy),
+            tolerance: None,
+            // `Slots` are nanoseconds here as well, which limits tolerances to temporal keys
+            tolerance_str: value
+                .tolerance
+                .as_ref()
+                .map(|tolerance| tolerance.to_polars_string().into()),
+            left_by: value.left_by.as_ref().map(smart_strings),
+            right_by: value.right_by.as_ref().map(smart_strings),
+        };
+        pl::JoinArgs {
+            how: pl::JoinType::AsOf(options),
+            suffix: value.suffix.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum UniqueKeepStrategy {
+    First,
+    Last,
+    #[rust_to_ocaml(name = "Keep_none")]
+    None,
+    Any,
+}
+
+impl From<&UniqueKeepStrategy> for pl::UniqueKeepStrategy {
+    fn from(value: &UniqueKeepStrategy) -> Self {
+        match value {
+            UniqueKeepStrategy::First => pl::UniqueKeepStrategy::First,
+            UniqueKeepStrategy::Last => pl::UniqueKeepStrategy::Last,
+            UniqueKeepStrategy::None => pl::UniqueKeepStrategy::None,
+            UniqueKeepStrategy::Any => pl::UniqueKeepStrategy::Any,
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum FillNullStrategy {
+    Backward(Option<u32>),
+    Forward(Option<u32>),
+    Mean,
+    Min,
+    Max,
+    Zero,
+    One,
+    MaxBound,
+    MinBound,
+}
+
+impl From<&FillNullStrategy> for pl::FillNullStrategy {
+    fn from(value: &FillNullStrategy) -> Self {
+        match value {
+            FillNullStrategy::Backward(limit) => pl::FillNullStrategy::Backward(*limit),
+            FillNullStrategy::Forward(limit) => pl::FillNullStrategy::Forward(*limit),
+            FillNullStrategy::Mean => pl::FillNullStrategy::Mean,
+            FillNullStrategy::Min => pl::FillNullStrategy::Min,
+            FillNullStrategy::Max => pl::FillNullStrategy::Max,
+            FillNullStrategy::Zero => pl::FillNullStrategy::Zero,
+            FillNullStrategy::One => pl::FillNullStrategy::One,
+            FillNullStrategy::MaxBound => pl::FillNullStrategy::MaxBound,
+            FillNullStrategy::MinBound => pl::FillNullStrategy::MinBound,
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub struct UnionArgs {
+    pub parallel: bool,
+    pub rechunk: bool,
+    pub to_supertypes: bool,
+    pub diagonal: bool,
+}
+
+impl From<&UnionArgs> for pl::UnionArgs {
+    fn from(value: &UnionArgs) -> Self {
+        pl::UnionArgs {
+            parallel: value.parallel,
+            rechunk: value.rechunk,
+            to_supertypes: value.to_supertypes,
+            diagonal: value.diagonal,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum Optimization {
+    PredicatePushdown,
+    ProjectionPushdown,
+    SlicePushdown,
+    CommSubplanElim,
+    CommSubexprElim,
+    TypeCoercion,
+    SimplifyExpr,
+}
+
+#[derive(FromValue, ToOcamlRep)]
+pub enum SinkControl {
+    Continue,
+    Stop,
+}
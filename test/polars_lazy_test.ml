@@ -178,3 +178,690 @@ let%expect_test "cumsum" =
   [%expect
     {| [0.0050 1.0250 1.0440 1.1300 3.2730 3.2740 3.2750 3.9470 4.0810 4.0910 4.0920 4.1000 4.1040 4.1050 15.1780 15.1790 15.1810 15.1920 15.1960 15.1970 15.2000 15.2010 15.2380 15.2510 15.3330 15.3410 15.3790 15.4170 15.5140 15.5440 16.0110 16.9460 17.1360 18.9160 19.6400 25.1570 26.1070 26.2620 26.2640 26.2750] |}]
 ;;
+
+let%expect_test "sink parquet" =
+  let df = Polars_testdata.trades1 () in
+  let filepath = Filename_unix.temp_file "trades-1-sink" ".parquet" in
+  Polars_lazy.create df |> sink_parquet filepath;
+  let df' = Polars.read_parquet filepath in
+  print_s [%sexp (Polars.equal df df' : bool)];
+  [%expect {| true |}]
+;;
+
+let%expect_test "sink ipc" =
+  let df = Polars_testdata.trades1 () in
+  let filepath = Filename_unix.temp_file "trades-1-sink" ".ipc" in
+  Polars_lazy.create df |> sink_ipc filepath;
+  let df' = Polars.read_ipc filepath in
+  print_s [%sexp (Polars.equal df df' : bool)];
+  [%expect {| true |}]
+;;
+
+let%expect_test "sink csv" =
+  let df = Polars_testdata.trades1 () in
+  let filepath = Filename_unix.temp_file "trades-1-sink" ".csv" in
+  Polars_lazy.create df |> sink_csv filepath;
+  let df' = Polars.read_csv ~schema:Polars_testdata.schema ~has_header:true filepath in
+  print_s [%sexp (Polars.equal df df' : bool)];
+  [%expect {| true |}];
+  let first_trade = col "trade_id" = int64 1845263590 in
+  Polars_lazy.create df
+  |> select
+       [| col "trade_id"
+        ; when_ first_trade |> then_ null |> otherwise (col "price") |> alias "price"
+       |]
+  |> sink_csv ~separator:';' ~null_value:"NA" filepath;
+  List.take (In_channel.read_lines filepath) 3 |> List.iter ~f:print_endline;
+  [%expect {|
+    trade_id;price
+    1845263590;NA
+    1845263591;25899.8 |}]
+;;
+
+let%expect_test "filter" =
+  let df = Polars_testdata.trades1 () in
+  let lo = lit (Int64 1694217620883) in
+  let hi = lit (Int64 1694217636694) in
+  let inside =
+    Polars_lazy.create df
+    |> filter (col "timestamp" > lo && col "timestamp" <= hi)
+    |> collect
+  in
+  let expected =
+    Polars.filter_col_by_name_multi
+      df
+      [| "timestamp", Gt, Polars.Int64 1694217620883
+       ; "timestamp", LtEq, Polars.Int64 1694217636694
+      |]
+  in
+  print_s [%sexp (Polars.equal inside expected : bool)];
+  [%expect {| true |}];
+  let outside =
+    Polars_lazy.create df
+    |> filter (col "timestamp" <= lo || col "timestamp" > hi)
+    |> collect
+  in
+  print_s [%sexp (Int.( + ) (Polars.length inside) (Polars.length outside) : int)];
+  [%expect {| 223 |}];
+  let crossed =
+    Polars_lazy.create df |> filter (col "first_id" != col "last_id") |> collect
+  in
+  [%test_result: bool] ~expect:false (Polars.is_empty crossed)
+;;
+
+let%expect_test "join" =
+  let df = Polars_testdata.trades1 () in
+  let prices = Polars.select df [| "trade_id"; "price" |] |> Polars_lazy.create in
+  let joined =
+    Polars_lazy.create df
+    |> join
+         ~validation:OneToOne
+         ~left_on:[| col "trade_id" |]
+         ~right_on:[| col "trade_id" |]
+         prices
+    |> collect
+  in
+  print_s [%sexp (Polars.column_names joined : string array)];
+  [%expect {| (trade_id price qty first_id last_id timestamp is_bid price_right) |}];
+  [%test_result: int] ~expect:223 (Polars.length joined);
+  let head = Polars.sub df ~pos:0 ~len:10 |> Polars_lazy.create in
+  let semi =
+    Polars_lazy.create df
+    |> join ~how:Semi ~left_on:[| col "trade_id" |] ~right_on:[| col "trade_id" |] head
+    |> collect
+  in
+  [%test_result: int] ~expect:10 (Polars.length semi);
+  let anti =
+    Polars_lazy.create df
+    |> join ~how:Anti ~left_on:[| col "trade_id" |] ~right_on:[| col "trade_id" |] head
+    |> collect
+  in
+  [%test_result: int] ~expect:213 (Polars.length anti);
+  let cross = head |> cross_join head |> collect in
+  [%test_result: int] ~expect:100 (Polars.length cross);
+  let eager =
+    Polars.join
+      ~how:Left
+      df
+      (Polars.sub df ~pos:0 ~len:10)
+      ~left_on:[| "trade_id" |]
+      ~right_on:[| "trade_id" |]
+  in
+  [%test_result: int] ~expect:223 (Polars.length eager);
+  [%test_result: int] ~expect:213 (Polars.null_counts eager).(7)
+;;
+
+let%expect_test "group_by_dynamic" =
+  let df = Polars_testdata.trades1 () in
+  let bars =
+    Polars_lazy.create df
+    |> with_column
+         (col "timestamp"
+          |> cast (Datetime Milliseconds)
+          |> set_sorted_flag Ascending
+          |> alias "ts")
+    |> group_by_dynamic
+         ~every:(Duration "10s")
+         ~include_boundaries:true
+         (col "ts")
+         [| first (col "price") |> alias "open"
+          ; max (col "price") |> alias "high"
+          ; min (col "price") |> alias "low"
+          ; last (col "price") |> alias "close"
+          ; sum (col "qty") |> alias "volume"
+          ; Len |> alias "trades"
+         |]
+    |> collect
+  in
+  print_s [%sexp (Polars.column_names bars : string array)];
+  [%expect {| (_lower_boundary _upper_boundary ts open high low close volume trades) |}];
+  let trades = Polars.select_by_name_exn bars ~col:"trades" |> Series.sum in
+  [%test_result: float] ~expect:223. trades
+;;
+
+let%expect_test "group_by_rolling" =
+  let df = Polars_testdata.trades1 () in
+  let rolled =
+    Polars_lazy.create df
+    |> with_column (col "timestamp" |> set_sorted_flag Ascending)
+    |> group_by_rolling
+         ~period:(Slots 1000)
+         (col "timestamp")
+         [| sum (col "qty") |> alias "volume"; Len |> alias "trades" |]
+    |> collect
+  in
+  print_s [%sexp (Polars.column_names rolled : string array)];
+  [%expect {| (timestamp volume trades) |}];
+  [%test_result: int] ~expect:223 (Polars.length rolled);
+  print_s [%sexp (Polars.get_by_name_exn rolled ~col:"volume" ~idx:0 : Any_value.t)];
+  [%expect {| (Float32 0.004999999888241291) |}]
+;;
+
+let%expect_test "sort by exprs and top_k" =
+  let df = Polars_testdata.trades1 () in
+  let sorted =
+    Polars_lazy.create df
+    |> sort_by_exprs ~descending:[| false; true |] [| col "is_bid"; col "timestamp" |]
+    |> collect
+  in
+  let last = Int.( - ) (Polars.length sorted) 1 in
+  print_s [%sexp (Polars.get_by_name_exn sorted ~col:"is_bid" ~idx:0 : Any_value.t)];
+  [%expect {| (Boolean false) |}];
+  print_s [%sexp (Polars.get_by_name_exn sorted ~col:"is_bid" ~idx:last : Any_value.t)];
+  [%expect {| (Boolean true) |}];
+  print_s
+    [%sexp (Polars.get_by_name_exn sorted ~col:"timestamp" ~idx:last : Any_value.t)];
+  [%expect {| (Int64 1694217604162) |}];
+  let by_qty = Polars.sort df ~descending:true ~col:"qty" in
+  let top = Polars_lazy.create df |> top_k 5 [| col "qty" |] |> collect in
+  [%test_result: int] ~expect:5 (Polars.length top);
+  [%test_result: Any_value.t]
+    ~expect:(Polars.get_by_name_exn by_qty ~col:"qty" ~idx:0)
+    (Polars.get_by_name_exn top ~col:"qty" ~idx:0);
+  let bottom = Polars_lazy.create df |> bottom_k 1 [| col "qty" |] |> collect in
+  let idx = Int.( - ) (Polars.length df) 1 in
+  [%test_result: Any_value.t]
+    ~expect:(Polars.get_by_name_exn by_qty ~col:"qty" ~idx)
+    (Polars.get_by_name_exn bottom ~col:"qty" ~idx:0)
+;;
+
+let%expect_test "sort with nulls_last per column" =
+  let ids = col "trade_id" in
+  let lazy_df =
+    Polars_lazy.create (Polars_testdata.trades1 ())
+    |> limit 6
+    |> with_columns
+         [| when_ (col "is_bid") |> then_ null |> otherwise (int64 1) |> alias "a"
+          ; when_ (ids < int64 1845263592) |> then_ null |> otherwise ids |> alias "b"
+         |]
+  in
+  let sorted_ids ~nulls_last =
+    let df = sort_by_exprs ~nulls_last [| col "a"; col "b" |] lazy_df |> collect in
+    List.init (Polars.length df) ~f:(fun idx ->
+      Polars.get_by_name_exn df ~col:"trade_id" ~idx |> Any_value.int64_exn)
+  in
+  print_s [%sexp (sorted_ids ~nulls_last:[| true; false |] : int list)];
+  [%expect {| (1845263590 1845263592 1845263594 1845263591 1845263593 1845263595) |}];
+  print_s [%sexp (sorted_ids ~nulls_last:[| true |] : int list)];
+  [%expect {| (1845263592 1845263594 1845263590 1845263593 1845263595 1845263591) |}];
+  let result =
+    Result.try_with (fun () -> sorted_ids ~nulls_last:[| true; false; true |])
+  in
+  print_s [%sexp (Result.is_error result : bool)];
+  [%expect {| true |}]
+;;
+
+let%expect_test "frame verbs" =
+  let df = Polars_testdata.trades1 () in
+  let lazy_df = Polars_lazy.create df in
+  let shape t = collect t |> Polars.shape in
+  print_s [%sexp (shape (limit 10 lazy_df) : int * int)];
+  [%expect {| (10 7) |}];
+  print_s [%sexp (shape (tail 5 lazy_df) : int * int)];
+  [%expect {| (5 7) |}];
+  print_s [%sexp (shape (slice ~offset:220 ~len:10 lazy_df) : int * int)];
+  [%expect {| (3 7) |}];
+  print_s [%sexp (shape (unique ~subset:[| "is_bid" |] lazy_df) : int * int)];
+  [%expect {| (2 7) |}];
+  print_s [%sexp (shape (drop_nulls lazy_df) : int * int)];
+  [%expect {| (223 7) |}];
+  let renamed =
+    lazy_df
+    |> drop [| "first_id"; "last_id" |]
+    |> rename ~existing:[| "qty" |] ~new_:[| "volume" |]
+    |> collect
+  in
+  print_s [%sexp (Polars.column_names renamed : string array)];
+  [%expect {| (trade_id price volume timestamp is_bid) |}];
+  let reversed = reverse lazy_df |> collect in
+  print_s [%sexp (Polars.get_by_name_exn reversed ~col:"timestamp" ~idx:0 : Any_value.t)];
+  [%expect {| (Int64 1694217660528) |}];
+  let shifted = shift 1 lazy_df |> collect in
+  [%test_result: int] ~expect:7 (Polars.null_count shifted);
+  let filled = shift 1 lazy_df |> fill_nulls_with_strategy (Backward None) |> collect in
+  [%test_result: int] ~expect:0 (Polars.null_count filled);
+  let filled = shift 1 lazy_df |> fill_nulls ~with_:(int64 0) |> collect in
+  [%test_result: int] ~expect:0 (Polars.null_count filled)
+;;
+
+let%expect_test "join_asof" =
+  let trades, quotes = Polars_testdata.asof_frames () in
+  let trades = Polars_lazy.create trades in
+  let quotes = Polars_lazy.create quotes in
+  (* trades are matched per side, unmatched trades get quote 0 *)
+  let test ?tolerance () =
+    let df =
+      trades
+      |> join_asof
+           ?tolerance
+           ~left_by:[| "sym" |]
+           ~right_by:[| "sym" |]
+           ~left_on:(col "timestamp")
+           ~right_on:(col "timestamp")
+           quotes
+      |> select [| col "trade_id"; col "quote_id" |> fill_null ~with_:(int64 0) |]
+      |> collect
+    in
+    for idx = 0 to Int.( - ) (Polars.length df) 1 do
+      print_s [%sexp (Polars.get_row_exn df ~idx : Any_value.t array)]
+    done
+  in
+  test ();
+  [%expect
+    {|
+    ((Int64 1845263590) (Int64 0))
+    ((Int64 1845263591) (Int64 0))
+    ((Int64 1845263592) (Int64 1845263590))
+    ((Int64 1845263593) (Int64 1845263591))
+    ((Int64 1845263594) (Int64 1845263592))
+    ((Int64 1845263595) (Int64 1845263593))
+    ((Int64 1845263596) (Int64 1845263594))
+    ((Int64 1845263597) (Int64 1845263595)) |}];
+  test ~tolerance:(Polars.Duration.of_int_ms 15) ();
+  [%expect
+    {|
+    ((Int64 1845263590) (Int64 0))
+    ((Int64 1845263591) (Int64 0))
+    ((Int64 1845263592) (Int64 0))
+    ((Int64 1845263593) (Int64 0))
+    ((Int64 1845263594) (Int64 1845263592))
+    ((Int64 1845263595) (Int64 0))
+    ((Int64 1845263596) (Int64 0))
+    ((Int64 1845263597) (Int64 1845263595)) |}]
+;;
+
+let%expect_test "concat" =
+  let df = Polars_testdata.trades1 () in
+  let lazy_df = Polars_lazy.create df in
+  let shape t = collect t |> Polars.shape in
+  print_s [%sexp (shape (concat [| lazy_df; lazy_df |]) : int * int)];
+  [%expect {| (446 7) |}];
+  let prices = select [| col "trade_id"; col "price" |] lazy_df in
+  let qtys = select [| col "trade_id"; col "qty" |] lazy_df |> limit 10 in
+  print_s [%sexp (shape (concat ~diagonal:true [| prices; qtys |]) : int * int)];
+  [%expect {| (233 3) |}];
+  let qtys = select [| col "qty" |] lazy_df in
+  print_s [%sexp (shape (concat_horizontal [| prices; qtys |]) : int * int)];
+  [%expect {| (223 3) |}]
+;;
+
+let%expect_test "explain" =
+  let df = Polars_testdata.trades1 () in
+  let lazy_df = Polars_lazy.create df |> filter (col "price" > lit (Float32 0.)) in
+  let has_filter plan = String.is_substring plan ~substring:"FILTER" in
+  print_s [%sexp (has_filter (explain ~optimized:false lazy_df) : bool)];
+  [%expect {| true |}];
+  (* the predicate is pushed down into the scan *)
+  print_s [%sexp (has_filter (explain lazy_df) : bool)];
+  [%expect {| false |}];
+  print_s [%sexp (String.is_substring (to_dot lazy_df) ~substring:"graph" : bool)];
+  [%expect {| true |}]
+;;
+
+let%expect_test "profile" =
+  let df = Polars_testdata.trades1 () in
+  let result, timings =
+    Polars_lazy.create df
+    |> with_columns [| col "price" * col "qty" |> alias "notional" |]
+    |> profile
+  in
+  print_s [%sexp (Polars.shape result : int * int)];
+  [%expect {| (223 8) |}];
+  print_s [%sexp (Polars.column_names timings : string array)];
+  [%expect {| (node start end) |}]
+;;
+
+let%expect_test "optimization toggles" =
+  let df = Polars_testdata.trades1 () in
+  let lazy_df = Polars_lazy.create df |> filter (col "price" > lit (Float32 0.)) in
+  let has_filter plan = String.is_substring plan ~substring:"FILTER" in
+  print_s [%sexp (has_filter (explain (with_predicate_pushdown false lazy_df)) : bool)];
+  [%expect {| true |}];
+  let streamed = collect ~streaming:true lazy_df in
+  print_s [%sexp (Polars.equal streamed (collect lazy_df) : bool)];
+  [%expect {| true |}]
+;;
+
+let%expect_test "collect_all" =
+  let df = Polars_testdata.trades1 () in
+  let lazy_df = Polars_lazy.create df in
+  let shapes =
+    collect_all [| lazy_df; limit 10 lazy_df; select [| col "price" |] lazy_df |]
+    |> Array.map ~f:Polars.shape
+  in
+  print_s [%sexp (shapes : (int * int) array)];
+  [%expect {| ((223 7) (10 7) (223 1)) |}];
+  (* the shared scan runs once for both frames *)
+  let scans = ref 0 in
+  let source _columns _n_rows =
+    Int.incr scans;
+    [| df |]
+  in
+  let shared =
+    scan ~schema:(schema lazy_df) source |> filter (col "qty" > float64 0.01)
+  in
+  let shapes =
+    collect_all [| select [| col "price" |] shared; select [| col "qty" |] shared |]
+    |> Array.map ~f:Polars.shape
+  in
+  print_s [%sexp (shapes : (int * int) array), (!scans : int)];
+  [%expect {| (((116 1) (116 1)) 1) |}]
+;;
+
+let%expect_test "schema" =
+  let df = Polars_testdata.trades1 () in
+  let lazy_df =
+    Polars_lazy.create df
+    |> with_columns [| col "price" * col "qty" |> alias "notional" |]
+    |> select [| col "trade_id"; col "notional"; col "is_bid" |]
+  in
+  print_s [%sexp (schema lazy_df : Polars.Schema.t)];
+  [%expect {| ((trade_id Int64) (notional Float32) (is_bid Boolean)) |}];
+  let prices = col "price" |> implode in
+  let lazy_df =
+    Polars_lazy.create df
+    |> select
+         [| prices |> alias "prices"
+          ; prices |> Lst.to_struct ~upper_bound:2 |> alias "pair"
+          ; col "trade_id" |> cast Int32 |> first |> alias "id"
+         |]
+  in
+  print_s [%sexp (schema lazy_df : Polars.Schema.t)];
+  [%expect
+    {|
+    ((prices (List Float32)) (pair (Struct ((field_0 Float32) (field_1 Float32))))
+     (id Int32)) |}];
+  (* the error names the column of the unsupported dtype *)
+  let nulls = Polars_lazy.create df |> select [| null |> alias "nothing" |] in
+  let result = Result.try_with (fun () -> schema nulls) in
+  print_s
+    [%sexp
+      (Result.error result
+       |> Option.value_map ~default:false ~f:(fun exn ->
+         String.is_substring (Exn.to_string exn) ~substring:"nothing")
+       : bool)];
+  [%expect {| true |}]
+;;
+
+let%expect_test "map_batches" =
+  let df = Polars_testdata.trades1 () in
+  let lazy_df = Polars_lazy.create df in
+  let double series = Series.multiply series (Float32 2.) in
+  let doubled =
+    lazy_df
+    |> select [| col "price" |> map_batches ~output_type:Float32 double |]
+    |> collect
+  in
+  let sum df = Polars.select_by_name_exn df ~col:"price" |> Series.sum in
+  [%test_result: float] ~expect:(Float.( * ) 2. (sum df)) (sum doubled);
+  let failing =
+    select [| col "price" |> map_batches ~output_type:Float32 (fun _ -> failwith "udf") |]
+  in
+  let result = Result.try_with (fun () -> failing lazy_df |> collect) in
+  print_s [%sexp (Result.is_error result : bool)];
+  [%expect {| true |}]
+;;
+
+let%expect_test "scan" =
+  let df = Polars_testdata.trades1 () in
+  let pushed_down = ref None in
+  let source columns n_rows =
+    pushed_down := Some (columns, n_rows);
+    [| Polars.sub df ~pos:0 ~len:100; Polars.sub df ~pos:100 ~len:123 |]
+  in
+  let lazy_df = scan ~schema:(schema (Polars_lazy.create df)) source in
+  print_s [%sexp (collect lazy_df |> Polars.shape : int * int)];
+  [%expect {| (223 7) |}];
+  let projected = lazy_df |> select [| col "price" |] |> limit 5 |> collect in
+  print_s [%sexp (Polars.shape projected : int * int)];
+  [%expect {| (5 1) |}];
+  print_s [%sexp (!pushed_down : (string array option * int option) option)];
+  [%expect {| (((price)) (5)) |}]
+;;
+
+let%expect_test "scan honouring the projection" =
+  let df = Polars_testdata.trades1 () in
+  let source columns _n_rows =
+    let df = Option.value_map columns ~default:df ~f:(Polars.select df) in
+    [| Polars.sub df ~pos:0 ~len:100; Polars.sub df ~pos:100 ~len:123 |]
+  in
+  let lazy_df = scan ~schema:(schema (Polars_lazy.create df)) source in
+  print_s [%sexp (collect lazy_df |> Polars.shape : int * int)];
+  [%expect {| (223 7) |}];
+  let projected = lazy_df |> select [| col "price"; col "qty" |] |> collect in
+  print_s [%sexp (Polars.shape projected : int * int)];
+  [%expect {| (223 2) |}];
+  let sum df = Polars.select_by_name_exn df ~col:"qty" |> Series.sum in
+  [%test_result: float] ~expect:(sum df) (sum projected)
+;;
+
+let%expect_test "sink batches" =
+  let df = Polars_testdata.trades1 () in
+  let lazy_df = Polars_lazy.create df in
+  let rows = ref 0 in
+  sink_batches
+    (fun batch ->
+      rows := Int.( + ) !rows (Polars.length batch);
+      Continue)
+    lazy_df;
+  [%test_result: int] ~expect:223 !rows;
+  let calls = ref 0 in
+  sink_batches
+    (fun _ ->
+      Int.incr calls;
+      Stop)
+    lazy_df;
+  [%test_result: int] ~expect:1 !calls
+;;
+
+let%expect_test "sql" =
+  let df = Polars_testdata.trades1 () in
+  let ctx = Sql.create () in
+  Sql.register_df ctx ~name:"trades" df;
+  Sql.register ctx ~name:"quotes" (Polars_lazy.create df |> limit 10);
+  let tables = Sql.tables ctx |> Array.sorted_copy ~compare:String.compare in
+  print_s [%sexp (tables : string array)];
+  [%expect {| (quotes trades) |}];
+  let query = "SELECT trade_id, price FROM trades WHERE is_bid" in
+  let bids = Sql.execute ctx query |> collect in
+  let expected =
+    Polars_lazy.create df
+    |> filter (col "is_bid")
+    |> select [| col "trade_id"; col "price" |]
+    |> collect
+  in
+  print_s [%sexp (Polars.equal bids expected : bool)];
+  [%expect {| true |}];
+  let notional =
+    Polars_lazy.create df |> select [| sql_expr "price * qty AS notional" |] |> collect
+  in
+  print_s [%sexp (Polars.column_names notional : string array)];
+  [%expect {| (notional) |}];
+  let result = Result.try_with (fun () -> sql_expr "price *") in
+  print_s [%sexp (Result.is_error result : bool)];
+  [%expect {| true |}]
+;;
+
+let%expect_test "string namespace" =
+  let df = Polars_testdata.trades1 () in
+  let id = col "id" in
+  let row =
+    Polars_lazy.create df
+    |> select [| col "first_id" |> cast String |> alias "id" |]
+    |> limit 1
+    |> select
+         [| id
+          ; Str.len_chars id |> alias "len"
+          ; Str.contains "974" id |> alias "contains"
+          ; Str.starts_with "407" id |> alias "starts_with"
+          ; Str.slice ~offset:0 ~length:3 id |> alias "slice"
+          ; Str.replace ~pattern:"407" ~with_:"x" id |> alias "replace"
+          ; Str.extract "(\\d{3})$" id |> alias "extract"
+          ; Str.zfill 12 id |> alias "zfill"
+          ; Str.pad_end ~fill_char:'_' 12 id |> alias "pad_end"
+          ; Str.to_date ~format:"%Y-%m-%d" (lit (String "2023-09-09")) |> alias "date"
+         |]
+    |> collect
+    |> Polars.get_row_exn ~idx:0
+  in
+  print_s [%sexp (row : Any_value.t array)];
+  [%expect
+    {|
+    ((String 4071974096) (UInt32 10) (Boolean true) (Boolean true) (String 407)
+     (String x1974096) (String 096) (String 004071974096) (String 4071974096__)
+     (Date 19609)) |}]
+;;
+
+let%expect_test "math" =
+  let df = Polars_testdata.trades1 () in
+  let price = col "price" in
+  let row =
+    Polars_lazy.create df
+    |> limit 1
+    |> select
+         [| float64 4. |> sqrt |> alias "sqrt"
+          ; float64 3. |> pow (float64 2.) |> alias "pow"
+          ; float64 27. |> cbrt |> alias "cbrt"
+          ; float64 (-2.5) |> sign |> alias "sign"
+          ; price |> clip ~max:(float64 25000.) |> alias "clip_max"
+          ; price |> clip ~min:(float64 26000.) ~max:(float64 27000.) |> alias "clip"
+          ; float64 0. |> cos |> alias "cos"
+          ; float64 Float.pi |> degrees |> alias "degrees"
+          ; atan2 (float64 0.) (float64 1.) |> alias "atan2"
+         |]
+    |> collect
+    |> Polars.get_row_exn ~idx:0
+  in
+  print_s [%sexp (row : Any_value.t array)];
+  [%expect
+    {|
+    ((Float64 2) (Float64 9) (Float64 3) (Float64 -1) (Float32 25000)
+     (Float32 26000) (Float64 1) (Float64 180) (Float64 0)) |}]
+;;
+
+let%expect_test "quantile" =
+  let df = Polars_testdata.trades1 () in
+  let q interpol = col "first_id" |> quantile ~interpol (float64 0.375) in
+  let row =
+    Polars_lazy.create df
+    |> limit 5
+    |> select
+         [| q Nearest |> alias "nearest"
+          ; q Lower |> alias "lower"
+          ; q Higher |> alias "higher"
+          ; q Midpoint |> alias "midpoint"
+          ; q Linear |> alias "linear"
+         |]
+    |> collect
+    |> Polars.get_row_exn ~idx:0
+  in
+  print_s [%sexp (row : Any_value.t array)];
+  [%expect
+    {|
+    ((Float64 4071974099) (Float64 4071974097) (Float64 4071974099)
+     (Float64 4071974098) (Float64 4071974098)) |}]
+;;
+
+let%expect_test "list namespace" =
+  let df = Polars_testdata.trades1 () in
+  let ids = col "ids" in
+  let row =
+    Polars_lazy.create df
+    |> limit 5
+    |> select [| col "first_id" |> implode |> alias "ids" |]
+    |> select
+         [| Lst.len ids |> alias "len"
+          ; Lst.get 1 ids |> alias "get"
+          ; Lst.head 2 ids |> Lst.sum |> alias "head"
+          ; Lst.tail 2 ids |> Lst.sum |> alias "tail"
+          ; Lst.max ids |> alias "max"
+          ; Lst.contains (int64 4071974099) ids |> alias "contains"
+          ; Lst.sort ~descending:true ids |> Lst.get 0 |> alias "sort"
+          ; Lst.eval (Lst.element // int64 2) ids
+            |> Lst.unique
+            |> Lst.len
+            |> alias "unique"
+          ; Lst.head 2 ids
+            |> Lst.eval (cast String Lst.element)
+            |> Lst.join ","
+            |> alias "join"
+         |]
+    |> collect
+    |> Polars.get_row_exn ~idx:0
+  in
+  print_s [%sexp (row : Any_value.t array)];
+  [%expect
+    {|
+    ((UInt32 5) (Int64 4071974097) (Int64 8143948193) (Int64 8143948202)
+     (Int64 4071974102) (Boolean true) (Int64 4071974102) (UInt32 4)
+     (String 4071974096,4071974097)) |}]
+;;
+
+let%expect_test "temporal namespace" =
+  let df = Polars_testdata.trades1 () in
+  let ts = col "ts" in
+  let row =
+    Polars_lazy.create df
+    |> select [| col "timestamp" |> cast (Datetime Milliseconds) |> alias "ts" |]
+    |> limit 1
+    |> select
+         [| Dt.year ts |> alias "year"
+          ; Dt.month ts |> alias "month"
+          ; Dt.day ts |> alias "day"
+          ; Dt.weekday ts |> alias "weekday"
+          ; Dt.ordinal_day ts |> alias "ordinal_day"
+          ; Dt.second ts |> alias "second"
+          ; Dt.millisecond ts |> alias "millisecond"
+          ; Dt.epoch ts |> alias "epoch"
+          ; Dt.strftime "%Y-%m-%d %H:%M:%S" ts |> alias "strftime"
+          ; Dt.truncate (Duration "1m") ts |> alias "truncate"
+          ; Dt.offset_by (Duration "1d") ts |> alias "offset_by"
+          ; Dt.month_start ts |> alias "month_start"
+         |]
+    |> collect
+    |> Polars.get_row_exn ~idx:0
+  in
+  print_s [%sexp (row : Any_value.t array)];
+  [%expect
+    {|
+    ((Int64 2023) (Int64 9) (Int64 9) (Int64 6) (Int64 252) (Int64 4) (Int64 155)
+     (Int64 1694217604) (String "2023-09-09 00:00:04")
+     (Datetime 1694217600000 Milliseconds) (Datetime 1694304004155 Milliseconds)
+     (Datetime 1693526400000 Milliseconds)) |}]
+;;
+
+let%expect_test "temporal ranges" =
+  let df = Polars_testdata.trades1 () in
+  let every = Duration "1s" in
+  let ts = col "timestamp" |> cast (Datetime Milliseconds) |> Dt.truncate every in
+  let grid =
+    Polars_lazy.create df
+    |> select [| datetime_range ~interval:every (min ts) (max ts) |> alias "ts" |]
+    |> collect
+  in
+  let rows, _ = Polars.shape grid in
+  let last = Int.( - ) rows 1 in
+  print_s [%sexp (rows : int)];
+  print_s [%sexp (Polars.get_row_exn grid ~idx:0 : Any_value.t array)];
+  print_s [%sexp (Polars.get_row_exn grid ~idx:last : Any_value.t array)];
+  [%expect
+    {|
+    57
+    ((Datetime 1694217604000 Milliseconds))
+    ((Datetime 1694217660000 Milliseconds)) |}];
+  let date v = int64 v |> cast (Date : data_type) in
+  let days =
+    Polars_lazy.create df
+    |> select
+         [| date_range ~closed_window:(Left : closed_window) ~interval:(Duration "1d")
+              (date 19609) (date 19612)
+            |> alias "day"
+         |]
+    |> collect
+  in
+  print_s [%sexp (Polars.get_row_exn days ~idx:0 : Any_value.t array)];
+  print_s [%sexp (Polars.shape days : int * int)];
+  [%expect {|
+    ((Date 19609))
+    (3 1) |}]
+;;
@@ -254,3 +254,139 @@ let%expect_test "get rows" =
   (Failure
     "OutOfBounds(ErrString(\"index 666 is out of bounds for sequence of length 223\"))") |}]
 ;;
+
+let%expect_test "write partitioned" =
+  let df = Polars_testdata.trades1 () in
+  let root = Filename_unix.temp_dir "trades-1" "partitioned" in
+  let files =
+    Polars.write_partitioned df ~max_rows_per_file:50 ~partition_by:[| "is_bid" |] root
+  in
+  let rows =
+    Array.sum (module Int) files ~f:(fun path ->
+      let df' = read_parquet path in
+      [%test_result: int] ~expect:6 (Polars.width df');
+      [%test_result: bool] ~expect:true (Polars.length df' <= 50);
+      Polars.length df')
+  in
+  [%test_result: int] ~expect:223 rows;
+  let dirs =
+    Array.map files ~f:(fun path -> Filename.dirname path |> Filename.basename)
+    |> Array.to_list
+    |> List.dedup_and_sort ~compare:String.compare
+  in
+  print_s [%sexp (dirs : string list)];
+  [%expect {| (is_bid=false is_bid=true) |}];
+  (* appending continues numbering after existing files *)
+  let files' =
+    Polars.write_partitioned df ~mode:Append ~partition_by:[| "is_bid" |] root
+  in
+  [%test_result: int] ~expect:2 (Array.length files');
+  Array.iter files' ~f:(fun path ->
+    [%test_result: bool] ~expect:false (Array.mem files path ~equal:String.equal))
+;;
+
+let%expect_test "write partitioned escapes keys" =
+  let root = Filename_unix.temp_dir "trades-1" "escaped" in
+  let df = Polars.sub (Polars_testdata.trades1 ()) ~pos:0 ~len:2 in
+  rename_in_place df ~col:"is_bid" ~name:"../a/b=c";
+  let files = Polars.write_partitioned df ~partition_by:[| "../a/b=c" |] root in
+  Array.iter files ~f:(fun path ->
+    print_endline (String.chop_prefix_exn path ~prefix:root));
+  [%expect
+    {|
+    /..%2Fa%2Fb%3Dc=false/part-0.parquet
+    /..%2Fa%2Fb%3Dc=true/part-0.parquet |}];
+  rename_in_place df ~col:"../a/b=c" ~name:"..";
+  let result =
+    Result.try_with (fun () -> write_partitioned df ~partition_by:[| ".." |] root)
+  in
+  print_s [%sexp (Result.is_error result : bool)];
+  [%expect {| true |}];
+  let result =
+    Result.try_with (fun () ->
+      write_partitioned df ~max_rows_per_file:0 ~partition_by:[| "trade_id" |] root)
+  in
+  print_s [%sexp (Result.is_error result : bool)];
+  [%expect {| true |}]
+;;
+
+let%expect_test "join_asof" =
+  let df = Polars_testdata.trades1 () in
+  let df = cast df ~col:"timestamp" ~dtype:(Datetime Milliseconds) in
+  let with_sorted_flag df ~col =
+    let series = select_by_name_exn df ~col in
+    Series.set_sorted_flag series ~flag:Ascending;
+    with_column df ~col:series
+  in
+  let trades =
+    select df [| "trade_id"; "timestamp" |] |> with_sorted_flag ~col:"timestamp"
+  in
+  let quotes = select df [| "timestamp"; "price" |] in
+  rename_in_place quotes ~col:"price" ~name:"quote";
+  let quotes = with_sorted_flag quotes ~col:"timestamp" in
+  let test ?tolerance strategy =
+    let df =
+      join_asof
+        ~strategy
+        ?tolerance
+        trades
+        quotes
+        ~left_on:"timestamp"
+        ~right_on:"timestamp"
+    in
+    [%test_result: int] ~expect:223 (length df);
+    let quote = select_by_name_exn df ~col:"quote" in
+    [%test_result: int] ~expect:0 (Series.null_count quote)
+  in
+  test Backward;
+  test Forward;
+  test Nearest ~tolerance:(Slots 0)
+;;
+
+let%expect_test "join_asof by" =
+  (* the matches are checked by the lazy test, the eager join must agree with it *)
+  let trades, quotes = Polars_testdata.asof_frames () in
+  let test ?tolerance () =
+    let eager =
+      join_asof
+        ?tolerance
+        ~left_by:[| "sym" |]
+        ~right_by:[| "sym" |]
+        trades
+        quotes
+        ~left_on:"timestamp"
+        ~right_on:"timestamp"
+    in
+    let lazy_ =
+      Polars_lazy.(
+        create trades
+        |> join_asof
+             ?tolerance
+             ~left_by:[| "sym" |]
+             ~right_by:[| "sym" |]
+             ~left_on:(col "timestamp")
+             ~right_on:(col "timestamp")
+             (create quotes)
+        |> collect)
+    in
+    print_s [%sexp (Polars.equal eager lazy_ : bool)]
+  in
+  test ();
+  [%expect {| true |}];
+  test ~tolerance:(Duration.of_int_ms 15) ();
+  [%expect {| true |}]
+;;
+
+let%expect_test "concat" =
+  let df = Polars_testdata.trades1 () in
+  print_s [%sexp (shape (concat [| df; df; df |]) : int * int)];
+  [%expect {| (669 7) |}];
+  let ids = select df [| "trade_id" |] in
+  let prices = select df [| "price" |] in
+  print_s [%sexp (shape (concat_horizontal [| ids; prices |]) : int * int)];
+  [%expect {| (223 2) |}];
+  rename_in_place prices ~col:"price" ~name:"price2";
+  let df = hstack df [| select_by_name_exn prices ~col:"price2" |] in
+  print_s [%sexp (shape df : int * int)];
+  [%expect {| (223 8) |}]
+;;
@@ -39,3 +39,24 @@ let trades2 =
 
 let trades1 () = Lazy.force trades1
 let trades2 () = Lazy.force trades2
+
+(* the first 8 trades and quotes arriving 1ms after the trade with the same id, both keyed
+   by the trade side in [sym] and sorted by [timestamp] *)
+let asof_frames () =
+  Polars_lazy.(
+    let df = create (trades1 ()) |> limit 8 in
+    let sym = col "is_bid" |> cast String |> alias "sym" in
+    let timestamp expr =
+      cast (Datetime Milliseconds) expr |> set_sorted_flag Ascending |> alias "timestamp"
+    in
+    let trades = select [| col "trade_id"; timestamp (col "timestamp"); sym |] df in
+    let quotes =
+      select
+        [| timestamp (col "timestamp" + int64 1)
+         ; sym
+         ; col "trade_id" |> alias "quote_id"
+        |]
+        df
+    in
+    collect trades, collect quotes)
+;;